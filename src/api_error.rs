@@ -0,0 +1,203 @@
+/// Known error returned by the api, parsed from its message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiErrorKind {
+    /// The free plan alias quota is reached
+    AliasLimitReached,
+    /// The action needs a premium plan
+    PremiumRequired,
+    /// The alias already exists
+    AliasAlreadyExists,
+    /// The alias was deleted before and can't be created again
+    AliasDeleted,
+    /// The signed suffix is expired, the alias options must be fetched again
+    AliasCreationExpired,
+    /// The alias prefix or the signed suffix is invalid
+    InvalidAliasSuffix,
+    /// A mailbox is not owned by the user or is not verified
+    InvalidMailbox,
+    /// At least one mailbox must be given
+    NoMailbox,
+    /// The email is already used by an account or a mailbox
+    EmailAlreadyUsed,
+    /// The email is invalid
+    InvalidEmail,
+    /// The email can't be used as a mailbox (e.g. disposable email)
+    EmailCannotBeUsed,
+    /// The mailbox is not verified
+    MailboxNotVerified,
+    /// The default mailbox can't be deleted
+    CannotDeleteDefaultMailbox,
+    /// The mailbox receiving the aliases of a deleted mailbox is invalid
+    InvalidTransferMailbox,
+    /// Wrong email or password on login
+    WrongCredentials,
+    /// The account is disabled
+    AccountDisabled,
+    /// The account is not activated
+    AccountNotActivated,
+    /// Wrong 2FA code
+    WrongMfaCode,
+    /// The mfa key given by the login is invalid
+    InvalidMfaKey,
+    /// Wrong activation code
+    WrongActivationCode,
+    /// The activation code is expired
+    ActivationCodeExpired,
+    /// Wrong password on sudo
+    WrongPassword,
+    /// The password is too short
+    PasswordTooShort,
+    /// The resource is not owned by the user
+    Forbidden,
+    /// The request body is malformed
+    InvalidRequest,
+    /// Unknown error
+    Other(String),
+}
+
+impl ApiErrorKind {
+    /// Classify an error message returned by the api
+    pub fn from_message(message: &str) -> Self {
+        let lower = message.to_lowercase();
+        let has = |pattern: &str| lower.contains(pattern);
+
+        if has("reached the limitation") {
+            Self::AliasLimitReached
+        } else if has("only premium") || has("upgrade your plan") {
+            Self::PremiumRequired
+        } else if lower.starts_with("alias ") && lower.ends_with(" already exists") {
+            Self::AliasAlreadyExists
+        } else if has("deleted this alias") || has("has been deleted") {
+            Self::AliasDeleted
+        } else if has("alias creation time is expired") {
+            Self::AliasCreationExpired
+        } else if has("tampered suffix") || has("wrong alias prefix or suffix") {
+            Self::InvalidAliasSuffix
+        } else if has("cannot delete default mailbox") {
+            Self::CannotDeleteDefaultMailbox
+        } else if has("transfer the aliases") {
+            Self::InvalidTransferMailbox
+        } else if has("not verified") {
+            Self::MailboxNotVerified
+        } else if has("errors with mailbox") {
+            Self::InvalidMailbox
+        } else if has("at least one mailbox") {
+            Self::NoMailbox
+        } else if has("already used") {
+            Self::EmailAlreadyUsed
+        } else if has("cannot be used") || has("as personal inbox") {
+            Self::EmailCannotBeUsed
+        } else if has("email or password incorrect") {
+            Self::WrongCredentials
+        } else if has("account disabled") {
+            Self::AccountDisabled
+        } else if has("not activated") {
+            Self::AccountNotActivated
+        } else if has("wrong totp") || has("wrong otp") {
+            Self::WrongMfaCode
+        } else if has("invalid mfa_key") {
+            Self::InvalidMfaKey
+        } else if has("wrong email or code") {
+            Self::WrongActivationCode
+        } else if has("code expired") || has("code is expired") {
+            Self::ActivationCodeExpired
+        } else if has("invalid password") {
+            Self::WrongPassword
+        } else if has("password too short") {
+            Self::PasswordTooShort
+        } else if is_invalid_email(&lower) || has("invalid email") {
+            Self::InvalidEmail
+        } else if lower == "forbidden" {
+            Self::Forbidden
+        } else if has("request body")
+            || has("must be a string")
+            || has("must be an array")
+            || has("must be a boolean")
+        {
+            Self::InvalidRequest
+        } else {
+            Self::Other(message.to_owned())
+        }
+    }
+}
+
+/// `<email> invalid`, sent by the mailbox creation
+fn is_invalid_email(message: &str) -> bool {
+    message
+        .strip_suffix(" invalid")
+        .is_some_and(|email| email.contains('@') && !email.contains(' '))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_message() {
+        let messages = [
+            (
+                "You have reached the limitation of a free account with the maximum of 10 aliases, please upgrade your plan to create more aliases",
+                ApiErrorKind::AliasLimitReached,
+            ),
+            ("alias already exists", ApiErrorKind::AliasAlreadyExists),
+            (
+                "alias prefix.suffix@simplelogin.co already exists",
+                ApiErrorKind::AliasAlreadyExists,
+            ),
+            (
+                "You have deleted this alias before. You can restore it on https://app.simplelogin.io/dashboard/trash",
+                ApiErrorKind::AliasDeleted,
+            ),
+            (
+                "Alias creation time is expired, please retry",
+                ApiErrorKind::AliasCreationExpired,
+            ),
+            ("Tampered suffix", ApiErrorKind::InvalidAliasSuffix),
+            (
+                "Cannot delete default mailbox",
+                ApiErrorKind::CannotDeleteDefaultMailbox,
+            ),
+            (
+                "Must choose at least one mailbox",
+                ApiErrorKind::NoMailbox,
+            ),
+            (
+                "user@example.com already used",
+                ApiErrorKind::EmailAlreadyUsed,
+            ),
+            ("user@example.com invalid", ApiErrorKind::InvalidEmail),
+            (
+                "user@example.com cannot be used. Please note a mailbox cannot be a disposable email address",
+                ApiErrorKind::EmailCannotBeUsed,
+            ),
+            (
+                "Email or password incorrect",
+                ApiErrorKind::WrongCredentials,
+            ),
+            ("Account disabled", ApiErrorKind::AccountDisabled),
+            ("Account not activated", ApiErrorKind::AccountNotActivated),
+            ("Wrong TOTP Token", ApiErrorKind::WrongMfaCode),
+            ("Invalid mfa_key", ApiErrorKind::InvalidMfaKey),
+            ("Invalid password", ApiErrorKind::WrongPassword),
+            ("password too short", ApiErrorKind::PasswordTooShort),
+            ("Forbidden", ApiErrorKind::Forbidden),
+            ("request body cannot be empty", ApiErrorKind::InvalidRequest),
+            (
+                "Domain example.com already exists",
+                ApiErrorKind::Other("Domain example.com already exists".to_owned()),
+            ),
+            (
+                "Contact already exists",
+                ApiErrorKind::Other("Contact already exists".to_owned()),
+            ),
+            (
+                "Domain name invalid",
+                ApiErrorKind::Other("Domain name invalid".to_owned()),
+            ),
+        ];
+
+        for (message, kind) in messages {
+            assert_eq!(ApiErrorKind::from_message(message), kind, "{message}");
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use async_trait::async_trait;
+use thiserror::Error;
+
+use crate::{ApiErrorKind, BaseHttpClient, Secret, SimpleLoginClient, SimpleLoginError};
+
+/// Provides the 2FA code when the account has MFA enabled
+#[async_trait]
+pub trait OtpProvider: Send + Sync {
+    /// Code for the account `email`, `None` to abort the login
+    async fn otp(&self, email: &str) -> Option<String>;
+}
+
+#[async_trait]
+impl<F> OtpProvider for F
+where
+    F: Fn(&str) -> Option<String> + Send + Sync,
+{
+    async fn otp(&self, email: &str) -> Option<String> {
+        self(email)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum AuthError {
+    #[error("Email or password incorrect")]
+    WrongCredentials,
+
+    #[error("Wrong 2FA code")]
+    WrongMfaCode,
+
+    #[error("2FA is enabled but no code was provided")]
+    MfaRequired,
+
+    #[error("Too many wrong tries, please ask for a reactivation 'api/auth/reactivate'")]
+    TooManyWrongTries,
+
+    #[error("Account not activated")]
+    InactiveAccount,
+
+    #[error("Account disabled")]
+    AccountDisabled,
+
+    #[error("No api key returned by the api")]
+    MissingApiKey,
+
+    #[error("2FA is enabled but no mfa key was returned by the api")]
+    MissingMfaKey,
+
+    #[error(transparent)]
+    Api(#[from] SimpleLoginError),
+}
+
+impl AuthError {
+    fn from_api(error: SimpleLoginError) -> Self {
+        if let SimpleLoginError::TooManyWrongTries { .. } = error {
+            return Self::TooManyWrongTries;
+        }
+
+        match error.api_error_kind() {
+            Some(ApiErrorKind::WrongCredentials) => Self::WrongCredentials,
+            Some(ApiErrorKind::WrongMfaCode) => Self::WrongMfaCode,
+            Some(ApiErrorKind::AccountNotActivated) => Self::InactiveAccount,
+            Some(ApiErrorKind::AccountDisabled) => Self::AccountDisabled,
+            _ => Self::Api(error),
+        }
+    }
+}
+
+/// Login process handling the 2FA, returning an authenticated client
+pub struct AuthFlow<'a, Http: BaseHttpClient> {
+    client: &'a SimpleLoginClient<Http>,
+    device: &'a str,
+    otp_provider: Option<&'a dyn OtpProvider>,
+}
+
+impl<'a, Http: BaseHttpClient> AuthFlow<'a, Http> {
+    pub fn new(client: &'a SimpleLoginClient<Http>, device: &'a str) -> Self {
+        Self {
+            client,
+            device,
+            otp_provider: None,
+        }
+    }
+
+    /// Provider asked for the 2FA code when the account has MFA enabled
+    pub fn otp_provider(mut self, otp_provider: &'a dyn OtpProvider) -> Self {
+        self.otp_provider = Some(otp_provider);
+        self
+    }
+
+    /// Log in and return a client authenticated with the new api key.
+    ///
+    /// The returned client shares the configuration of the original client but has its own token
+    pub async fn login(
+        self,
+        email: &str,
+        password: &Secret,
+    ) -> Result<SimpleLoginClient<Http>, AuthError> {
+        let login = self
+            .client
+            .account()
+            .login(email, password, self.device)
+            .await
+            .map_err(AuthError::from_api)?;
+
+        let api_key = if login.mfa_enabled {
+            let mfa_key = login.mfa_key.ok_or(AuthError::MissingMfaKey)?;
+
+            let otp_provider = self.otp_provider.ok_or(AuthError::MfaRequired)?;
+            let code = otp_provider
+                .otp(&login.email)
+                .await
+                .ok_or(AuthError::MfaRequired)?;
+
+            self.client
+                .account()
+                .mfa(&code, &mfa_key, self.device)
+                .await
+                .map_err(AuthError::from_api)?
+                .api_key
+        } else {
+            login.api_key.ok_or(AuthError::MissingApiKey)?
+        };
+
+        Ok(self.client.clone_with_token(api_key))
+    }
+}
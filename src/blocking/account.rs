@@ -0,0 +1,121 @@
+use crate::{
+    account::{
+        ApiKeyData, ApiKeyRotationData, CookieTokenData, LoginData, MfaData, StatsData,
+        UserInfoData,
+    },
+    BaseHttpClient, HttpClient, MsgData, OkData, Secret, SimpleLoginResult,
+};
+
+use super::SimpleLoginClient;
+
+pub struct EndpointsAccount<'a, Http: BaseHttpClient = HttpClient>(
+    pub(crate) &'a SimpleLoginClient<Http>,
+);
+
+impl<Http: BaseHttpClient> EndpointsAccount<'_, Http> {
+    /// Authentication
+    pub fn login(
+        self,
+        email: &str,
+        password: &Secret,
+        device: &str,
+    ) -> SimpleLoginResult<LoginData> {
+        self.0
+            .block_on(self.0.inner.account().login(email, password, device))
+    }
+
+    /// 2FA authentication
+    pub fn mfa(
+        self,
+        mfa_token: &str,
+        mfa_key: &Secret,
+        device: &str,
+    ) -> SimpleLoginResult<MfaData> {
+        self.0
+            .block_on(self.0.inner.account().mfa(mfa_token, mfa_key, device))
+    }
+
+    /// Register a new account
+    pub fn register(self, email: &str, password: &Secret) -> SimpleLoginResult<MsgData> {
+        self.0
+            .block_on(self.0.inner.account().register(email, password))
+    }
+
+    /// Activate new account
+    pub fn activate(self, email: &str, code: &str) -> SimpleLoginResult<MsgData> {
+        self.0
+            .block_on(self.0.inner.account().activate(email, code))
+    }
+
+    /// Request a new activation code
+    pub fn reactivate(self, email: &str) -> SimpleLoginResult<MsgData> {
+        self.0.block_on(self.0.inner.account().reactivate(email))
+    }
+
+    /// Request reset password link
+    pub fn forgot_password(self, email: &str) -> SimpleLoginResult<OkData> {
+        self.0
+            .block_on(self.0.inner.account().forgot_password(email))
+    }
+
+    /// Get user's information
+    pub fn get_user_info(self) -> SimpleLoginResult<UserInfoData> {
+        self.0.block_on(self.0.inner.account().get_user_info())
+    }
+
+    /// Get user's statistics
+    pub fn stats(self) -> SimpleLoginResult<StatsData> {
+        self.0.block_on(self.0.inner.account().stats())
+    }
+
+    /// Enable sudo mode
+    pub fn sudo(self, password: &Secret) -> SimpleLoginResult<OkData> {
+        self.0.block_on(self.0.inner.account().sudo(password))
+    }
+
+    /// Delete the current user
+    pub fn delete_user(self) -> SimpleLoginResult<OkData> {
+        self.0.block_on(self.0.inner.account().delete_user())
+    }
+
+    /// Get a one time use token to exchange it for a valid cookie
+    pub fn cookie_token(self) -> SimpleLoginResult<CookieTokenData> {
+        self.0.block_on(self.0.inner.account().cookie_token())
+    }
+
+    /// Get a url opening the dashboard already signed in, see [`crate::EndpointsAccount::web_login_url`]
+    pub fn web_login_url(self, next: Option<&str>) -> SimpleLoginResult<Secret> {
+        self.0.block_on(self.0.inner.account().web_login_url(next))
+    }
+
+    /// Update user's information
+    pub fn update_user_info(
+        self,
+        profile_picture: (bool, Option<&str>),
+        name: Option<&str>,
+    ) -> SimpleLoginResult<UserInfoData> {
+        self.0.block_on(
+            self.0
+                .inner
+                .account()
+                .update_user_info(profile_picture, name),
+        )
+    }
+
+    /// Create a new API key
+    pub fn create_api_key(self, device: &str) -> SimpleLoginResult<ApiKeyData> {
+        self.0
+            .block_on(self.0.inner.account().create_api_key(device))
+    }
+
+    /// Log out
+    pub fn logout(self) -> SimpleLoginResult<MsgData> {
+        self.0.block_on(self.0.inner.account().logout())
+    }
+
+    /// Replace the api key of the client by a new one, see [`crate::EndpointsAccount::rotate_api_key`]
+    pub fn rotate_api_key(self, device: &str) -> SimpleLoginResult<ApiKeyRotationData> {
+        self.0
+            .block_on(self.0.inner.account().rotate_api_key(device))
+    }
+}
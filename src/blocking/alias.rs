@@ -0,0 +1,131 @@
+use crate::{
+    alias::{AliasActivityData, AliasContactData, AliasData, AliasToggleData, OptionsData},
+    AliasFilter, AliasListOptions, AliasMode, BaseHttpClient, DeletedData, HttpClient, OkData,
+    SimpleLoginResult,
+};
+
+use super::SimpleLoginClient;
+
+pub struct EndpointsAlias<'a, Http: BaseHttpClient = HttpClient>(
+    pub(crate) &'a SimpleLoginClient<Http>,
+);
+
+impl<Http: BaseHttpClient> EndpointsAlias<'_, Http> {
+    /// Get alias options. Used by create alias process
+    pub fn options(self) -> SimpleLoginResult<OptionsData> {
+        self.0.block_on(self.0.inner.alias().options())
+    }
+
+    /// Create new alias
+    pub fn create_custom(
+        self,
+        alias_prefix: &str,
+        signed_suffix: &str,
+        mailbox_ids: &[usize],
+        note: Option<&str>,
+        name: Option<&str>,
+    ) -> SimpleLoginResult<AliasData> {
+        self.0.block_on(self.0.inner.alias().create_custom(
+            alias_prefix,
+            signed_suffix,
+            mailbox_ids,
+            note,
+            name,
+        ))
+    }
+
+    /// Random an alias
+    pub fn create_random(
+        self,
+        mode: Option<AliasMode>,
+        note: Option<&str>,
+    ) -> SimpleLoginResult<AliasData> {
+        self.0
+            .block_on(self.0.inner.alias().create_random(mode, note))
+    }
+
+    /// Get user's aliases
+    pub fn list(self, page_id: usize, filter: AliasFilter) -> SimpleLoginResult<Vec<AliasData>> {
+        self.0.block_on(self.0.inner.alias().list(page_id, filter))
+    }
+
+    /// Search user's aliases by email, note or name
+    pub fn search(self, query: &str, page_id: usize) -> SimpleLoginResult<Vec<AliasData>> {
+        self.0.block_on(self.0.inner.alias().search(query, page_id))
+    }
+
+    /// Get user's aliases matching a filter and a search query
+    pub fn list_with_options(
+        self,
+        page_id: usize,
+        options: AliasListOptions<'_>,
+    ) -> SimpleLoginResult<Vec<AliasData>> {
+        self.0
+            .block_on(self.0.inner.alias().list_with_options(page_id, options))
+    }
+
+    /// Get alias information
+    pub fn get(self, alias_id: usize) -> SimpleLoginResult<AliasData> {
+        self.0.block_on(self.0.inner.alias().get(alias_id))
+    }
+
+    /// Delete an alias
+    pub fn delete(self, alias_id: usize) -> SimpleLoginResult<DeletedData> {
+        self.0.block_on(self.0.inner.alias().delete(alias_id))
+    }
+
+    /// Enable/disable an alias
+    pub fn toggle(self, alias_id: usize) -> SimpleLoginResult<AliasToggleData> {
+        self.0.block_on(self.0.inner.alias().toggle(alias_id))
+    }
+
+    /// Get alias activities
+    pub fn activities(
+        self,
+        page_id: usize,
+        alias_id: usize,
+    ) -> SimpleLoginResult<Vec<AliasActivityData>> {
+        self.0
+            .block_on(self.0.inner.alias().activities(page_id, alias_id))
+    }
+
+    /// Update alias information
+    pub fn update(
+        self,
+        alias_id: usize,
+        note: Option<&str>,
+        name: Option<&str>,
+        mailbox_ids: Option<&[usize]>,
+        disable_pgp: Option<bool>,
+        pinned: Option<bool>,
+    ) -> SimpleLoginResult<OkData> {
+        self.0.block_on(self.0.inner.alias().update(
+            alias_id,
+            note,
+            name,
+            mailbox_ids,
+            disable_pgp,
+            pinned,
+        ))
+    }
+
+    /// Get alias contacts
+    pub fn contacts(
+        self,
+        page_id: usize,
+        alias_id: usize,
+    ) -> SimpleLoginResult<Vec<AliasContactData>> {
+        self.0
+            .block_on(self.0.inner.alias().contacts(page_id, alias_id))
+    }
+
+    /// Create a new contact for an alias
+    pub fn create_contact(
+        self,
+        alias_id: usize,
+        contact: &str,
+    ) -> SimpleLoginResult<AliasContactData> {
+        self.0
+            .block_on(self.0.inner.alias().create_contact(alias_id, contact))
+    }
+}
@@ -0,0 +1,45 @@
+use crate::{AuthError, BaseHttpClient, HttpClient, OtpProvider, Secret};
+
+use super::SimpleLoginClient;
+
+/// Login process handling the 2FA, see [`crate::AuthFlow`]
+pub struct AuthFlow<'a, Http: BaseHttpClient = HttpClient> {
+    client: &'a SimpleLoginClient<Http>,
+    device: &'a str,
+    otp_provider: Option<&'a dyn OtpProvider>,
+}
+
+impl<'a, Http: BaseHttpClient> AuthFlow<'a, Http> {
+    pub fn new(client: &'a SimpleLoginClient<Http>, device: &'a str) -> Self {
+        Self {
+            client,
+            device,
+            otp_provider: None,
+        }
+    }
+
+    /// Provider asked for the 2FA code when the account has MFA enabled
+    pub fn otp_provider(mut self, otp_provider: &'a dyn OtpProvider) -> Self {
+        self.otp_provider = Some(otp_provider);
+        self
+    }
+
+    /// Log in and return a client authenticated with the new api key
+    pub fn login(
+        self,
+        email: &str,
+        password: &Secret,
+    ) -> Result<SimpleLoginClient<Http>, AuthError> {
+        let mut flow = crate::AuthFlow::new(&self.client.inner, self.device);
+        if let Some(otp_provider) = self.otp_provider {
+            flow = flow.otp_provider(otp_provider);
+        }
+
+        let inner = self.client.block_on(flow.login(email, password))?;
+
+        Ok(SimpleLoginClient {
+            inner,
+            runtime: self.client.runtime.clone(),
+        })
+    }
+}
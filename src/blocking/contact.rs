@@ -0,0 +1,21 @@
+use crate::{
+    contact::ToggleContactData, BaseHttpClient, DeletedData, HttpClient, SimpleLoginResult,
+};
+
+use super::SimpleLoginClient;
+
+pub struct EndpointsContact<'a, Http: BaseHttpClient = HttpClient>(
+    pub(crate) &'a SimpleLoginClient<Http>,
+);
+
+impl<Http: BaseHttpClient> EndpointsContact<'_, Http> {
+    /// Delete a contact
+    pub fn delete(self, contact_id: usize) -> SimpleLoginResult<DeletedData> {
+        self.0.block_on(self.0.inner.contact().delete(contact_id))
+    }
+
+    /// Toggle a contact
+    pub fn toggle(self, contact_id: usize) -> SimpleLoginResult<ToggleContactData> {
+        self.0.block_on(self.0.inner.contact().toggle(contact_id))
+    }
+}
@@ -0,0 +1,41 @@
+use crate::{
+    custom_domain::{CustomDomainData, DeletedAliasData},
+    BaseHttpClient, HttpClient, SimpleLoginResult,
+};
+
+use super::SimpleLoginClient;
+
+pub struct EndpointsCustomDomain<'a, Http: BaseHttpClient = HttpClient>(
+    pub(crate) &'a SimpleLoginClient<Http>,
+);
+
+impl<Http: BaseHttpClient> EndpointsCustomDomain<'_, Http> {
+    /// Get custom domains
+    pub fn list(self) -> SimpleLoginResult<Vec<CustomDomainData>> {
+        self.0.block_on(self.0.inner.custom_domain().list())
+    }
+
+    /// Update custom domain's information
+    pub fn update(
+        self,
+        custom_domain_id: usize,
+        catch_all: Option<bool>,
+        random_prefix_generation: Option<bool>,
+        name: Option<&str>,
+        mailbox_ids: Option<&[usize]>,
+    ) -> SimpleLoginResult<CustomDomainData> {
+        self.0.block_on(self.0.inner.custom_domain().update(
+            custom_domain_id,
+            catch_all,
+            random_prefix_generation,
+            name,
+            mailbox_ids,
+        ))
+    }
+
+    /// Get deleted aliases of a custom domain
+    pub fn trash(self, custom_domain_id: usize) -> SimpleLoginResult<Vec<DeletedAliasData>> {
+        self.0
+            .block_on(self.0.inner.custom_domain().trash(custom_domain_id))
+    }
+}
@@ -0,0 +1,31 @@
+use serde_json::Value;
+
+use crate::{export::AliasExportData, BaseHttpClient, HttpClient, SimpleLoginResult};
+
+use super::SimpleLoginClient;
+
+pub struct EndpointsExport<'a, Http: BaseHttpClient = HttpClient>(
+    pub(crate) &'a SimpleLoginClient<Http>,
+);
+
+impl<Http: BaseHttpClient> EndpointsExport<'_, Http> {
+    /// Export all user's data (aliases, mailboxes, custom domains, contacts)
+    pub fn data(self) -> SimpleLoginResult<Value> {
+        self.0.block_on(self.0.inner.export().data())
+    }
+
+    /// Export all user's data as returned by the api, e.g. for archival
+    pub fn data_raw(self) -> SimpleLoginResult<Vec<u8>> {
+        self.0.block_on(self.0.inner.export().data_raw())
+    }
+
+    /// Export user's aliases
+    pub fn aliases(self) -> SimpleLoginResult<Vec<AliasExportData>> {
+        self.0.block_on(self.0.inner.export().aliases())
+    }
+
+    /// Export user's aliases as the csv returned by the api, e.g. for archival
+    pub fn aliases_raw(self) -> SimpleLoginResult<Vec<u8>> {
+        self.0.block_on(self.0.inner.export().aliases_raw())
+    }
+}
@@ -0,0 +1,67 @@
+use crate::{
+    mailbox::{MailboxData, RetiredMailboxData},
+    BaseHttpClient, DeletedData, HttpClient, SimpleLoginResult, UpdatedData,
+};
+
+use super::SimpleLoginClient;
+
+pub struct EndpointsMailbox<'a, Http: BaseHttpClient = HttpClient>(
+    pub(crate) &'a SimpleLoginClient<Http>,
+);
+
+impl<Http: BaseHttpClient> EndpointsMailbox<'_, Http> {
+    /// Get mailboxes
+    pub fn list(self) -> SimpleLoginResult<Vec<MailboxData>> {
+        self.0.block_on(self.0.inner.mailbox().list())
+    }
+
+    /// Get the default mailbox
+    pub fn get_default(self) -> SimpleLoginResult<Option<MailboxData>> {
+        self.0.block_on(self.0.inner.mailbox().get_default())
+    }
+
+    /// Create a new mailbox
+    pub fn create(self, email: &str) -> SimpleLoginResult<MailboxData> {
+        self.0.block_on(self.0.inner.mailbox().create(email))
+    }
+
+    /// Delete a mailbox. Its aliases are moved to `transfer_aliases_to`, or deleted if `None`
+    pub fn delete(
+        self,
+        mailbox_id: usize,
+        transfer_aliases_to: Option<usize>,
+    ) -> SimpleLoginResult<DeletedData> {
+        self.0.block_on(
+            self.0
+                .inner
+                .mailbox()
+                .delete(mailbox_id, transfer_aliases_to),
+        )
+    }
+
+    /// Delete a mailbox after checking that the target receiving its aliases is verified
+    pub fn retire(
+        self,
+        mailbox_id: usize,
+        target_id: usize,
+    ) -> SimpleLoginResult<RetiredMailboxData> {
+        self.0
+            .block_on(self.0.inner.mailbox().retire(mailbox_id, target_id))
+    }
+
+    /// Update a mailbox
+    pub fn update(
+        self,
+        mailbox_id: usize,
+        default: Option<bool>,
+        email: Option<&str>,
+        cancel_email_change: Option<bool>,
+    ) -> SimpleLoginResult<UpdatedData> {
+        self.0.block_on(self.0.inner.mailbox().update(
+            mailbox_id,
+            default,
+            email,
+            cancel_email_change,
+        ))
+    }
+}
@@ -0,0 +1,171 @@
+//! Synchronous api, enabled with the `blocking` feature.
+//!
+//! The endpoints are the same as the async ones and are driven by a runtime owned by
+//! the client, so no async runtime is needed. The methods must not be called from
+//! within an async context.
+
+use std::future::Future;
+use std::sync::Arc;
+
+use reqwest::Method;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tokio::runtime::{Builder, Runtime};
+
+pub use account::*;
+pub use alias::*;
+pub use auth::*;
+pub use contact::*;
+pub use custom_domain::*;
+pub use export::*;
+pub use mailbox::*;
+pub use notification::*;
+pub use setting::*;
+
+use crate::{
+    BaseHttpClient, HttpClient, Query, Secret, SimpleLoginClientBuilder, SimpleLoginResult,
+    SudoPolicy, TokenProvider,
+};
+
+mod account;
+mod alias;
+mod auth;
+mod contact;
+mod custom_domain;
+mod export;
+mod mailbox;
+mod notification;
+mod setting;
+
+/// Blocking client of the api, wrapping an async [`crate::SimpleLoginClient`].
+///
+/// The client is cheap to clone, the clones share the same runtime and token.
+#[derive(Debug, Clone)]
+pub struct SimpleLoginClient<Http: BaseHttpClient = HttpClient> {
+    inner: crate::SimpleLoginClient<Http>,
+    runtime: Arc<Runtime>,
+}
+
+impl SimpleLoginClient {
+    pub fn new(hostname: &str) -> Self {
+        Self::from_async(crate::SimpleLoginClient::new(hostname))
+    }
+
+    /// Build a blocking client from a configured builder
+    pub fn from_builder(builder: SimpleLoginClientBuilder) -> SimpleLoginResult<Self> {
+        Ok(Self::from_async(builder.build()?))
+    }
+}
+
+impl<Http: BaseHttpClient> SimpleLoginClient<Http> {
+    /// Wrap an async client
+    pub fn from_async(inner: crate::SimpleLoginClient<Http>) -> Self {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("Unable to start the runtime of the blocking client");
+
+        Self {
+            inner,
+            runtime: Arc::new(runtime),
+        }
+    }
+
+    /// Get the wrapped async client
+    pub fn as_async(&self) -> &crate::SimpleLoginClient<Http> {
+        &self.inner
+    }
+
+    /// Set the token used to authenticate the requests
+    pub fn with_token<S: Into<Secret>>(self, token: S) -> Self {
+        self.inner.set_token(token);
+        self
+    }
+
+    /// Enable the sudo mode automatically when an endpoint requires it, see [`crate::SudoPolicy`]
+    pub fn with_sudo_policy(mut self, sudo_policy: SudoPolicy) -> Self {
+        self.inner = self.inner.with_sudo_policy(sudo_policy);
+        self
+    }
+
+    /// Log in, handling the 2FA, see [`crate::AuthFlow`]
+    pub fn auth_flow<'a>(&'a self, device: &'a str) -> AuthFlow<'a, Http> {
+        AuthFlow::new(self, device)
+    }
+
+    /// Call an endpoint that is not wrapped by this crate, see [`crate::SimpleLoginClient::request`]
+    pub fn request<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        endpoint: &str,
+        query: Option<&Query<'_>>,
+        body: Option<&Value>,
+    ) -> SimpleLoginResult<T> {
+        self.block_on(self.inner.request(method, endpoint, query, body))
+    }
+
+    pub fn base_url(&self) -> &str {
+        self.inner.base_url()
+    }
+
+    /// Current token, asked to the token provider
+    pub fn token(&self) -> SimpleLoginResult<Option<Arc<str>>> {
+        self.block_on(self.inner.token())
+    }
+
+    /// Replace the token at runtime, see [`crate::SimpleLoginClient::set_token`]
+    pub fn set_token<S: Into<Secret>>(&self, token: S) {
+        self.inner.set_token(token);
+    }
+
+    /// Get the token from a provider consulted before each request, see [`crate::TokenProvider`]
+    pub fn with_token_provider<P: TokenProvider + 'static>(self, token_provider: P) -> Self {
+        self.inner.set_token_provider(token_provider);
+        self
+    }
+
+    /// Replace the token provider at runtime, see [`crate::SimpleLoginClient::set_token_provider`]
+    pub fn set_token_provider<P: TokenProvider + 'static>(&self, token_provider: P) {
+        self.inner.set_token_provider(token_provider);
+    }
+
+    pub fn clear_token(&self) {
+        self.inner.clear_token();
+    }
+
+    pub fn account(&self) -> EndpointsAccount<'_, Http> {
+        EndpointsAccount(self)
+    }
+
+    pub fn alias(&self) -> EndpointsAlias<'_, Http> {
+        EndpointsAlias(self)
+    }
+
+    pub fn mailbox(&self) -> EndpointsMailbox<'_, Http> {
+        EndpointsMailbox(self)
+    }
+
+    pub fn custom_domain(&self) -> EndpointsCustomDomain<'_, Http> {
+        EndpointsCustomDomain(self)
+    }
+
+    pub fn contact(&self) -> EndpointsContact<'_, Http> {
+        EndpointsContact(self)
+    }
+
+    pub fn notification(&self) -> EndpointsNotification<'_, Http> {
+        EndpointsNotification(self)
+    }
+
+    pub fn setting(&self) -> EndpointsSetting<'_, Http> {
+        EndpointsSetting(self)
+    }
+
+    pub fn export(&self) -> EndpointsExport<'_, Http> {
+        EndpointsExport(self)
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+}
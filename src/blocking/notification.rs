@@ -0,0 +1,22 @@
+use crate::{
+    notification::NotificationsData, BaseHttpClient, HttpClient, OkData, SimpleLoginResult,
+};
+
+use super::SimpleLoginClient;
+
+pub struct EndpointsNotification<'a, Http: BaseHttpClient = HttpClient>(
+    pub(crate) &'a SimpleLoginClient<Http>,
+);
+
+impl<Http: BaseHttpClient> EndpointsNotification<'_, Http> {
+    /// Get notifications
+    pub fn list(self, page: usize) -> SimpleLoginResult<NotificationsData> {
+        self.0.block_on(self.0.inner.notification().list(page))
+    }
+
+    /// Mark as read a notification
+    pub fn read(self, notification_id: usize) -> SimpleLoginResult<OkData> {
+        self.0
+            .block_on(self.0.inner.notification().read(notification_id))
+    }
+}
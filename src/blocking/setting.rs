@@ -0,0 +1,41 @@
+use crate::{
+    setting::{SettingData, SettingDomainData},
+    AliasGenerator, AliasRandomAliasSuffix, AliasSenderFormat, BaseHttpClient, HttpClient,
+    SimpleLoginResult,
+};
+
+use super::SimpleLoginClient;
+
+pub struct EndpointsSetting<'a, Http: BaseHttpClient = HttpClient>(
+    pub(crate) &'a SimpleLoginClient<Http>,
+);
+
+impl<Http: BaseHttpClient> EndpointsSetting<'_, Http> {
+    /// Get user's settings
+    pub fn get(self) -> SimpleLoginResult<SettingData> {
+        self.0.block_on(self.0.inner.setting().get())
+    }
+
+    /// Update user's settings
+    pub fn update(
+        self,
+        alias_generator: Option<AliasGenerator>,
+        notification: Option<bool>,
+        random_alias_default_domain: Option<&str>,
+        random_alias_suffix: Option<AliasRandomAliasSuffix>,
+        sender_format: Option<AliasSenderFormat>,
+    ) -> SimpleLoginResult<SettingData> {
+        self.0.block_on(self.0.inner.setting().update(
+            alias_generator,
+            notification,
+            random_alias_default_domain,
+            random_alias_suffix,
+            sender_format,
+        ))
+    }
+
+    /// Get domains that user can use to create random alias
+    pub fn domains(self) -> SimpleLoginResult<Vec<SettingDomainData>> {
+        self.0.block_on(self.0.inner.setting().domains())
+    }
+}
@@ -0,0 +1,186 @@
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use reqwest::{Certificate, Proxy, Url};
+
+use crate::{
+    HttpClient, Middleware, Middlewares, RateLimiter, RetryPolicy, Secret, SimpleLoginClient,
+    SimpleLoginError, SimpleLoginResult, StaticToken, SudoPolicy, TokenProvider,
+};
+
+/// Builder of a [`SimpleLoginClient`] with a configured [`HttpClient`]
+#[derive(Debug)]
+pub struct SimpleLoginClientBuilder {
+    base_url: String,
+    token: Option<Arc<dyn TokenProvider>>,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    user_agent: Option<String>,
+    proxies: Vec<Proxy>,
+    no_proxy: bool,
+    root_certificates: Vec<Certificate>,
+    retry_policy: RetryPolicy,
+    rate_limiter: Option<RateLimiter>,
+    middlewares: Middlewares,
+    sudo_policy: Option<SudoPolicy>,
+}
+
+impl SimpleLoginClientBuilder {
+    /// Create a builder for the instance at `base_url` (e.g. `https://app.simplelogin.io`
+    /// or `http://localhost:7777/simplelogin`)
+    pub fn new<S: Into<String>>(base_url: S) -> Self {
+        Self {
+            base_url: base_url.into(),
+            token: None,
+            connect_timeout: None,
+            read_timeout: None,
+            timeout: None,
+            user_agent: None,
+            proxies: Vec::new(),
+            no_proxy: false,
+            root_certificates: Vec::new(),
+            retry_policy: RetryPolicy::default(),
+            rate_limiter: None,
+            middlewares: Middlewares::default(),
+            sudo_policy: None,
+        }
+    }
+
+    pub fn token<S: Into<Secret>>(mut self, token: S) -> Self {
+        self.token = Some(Arc::new(StaticToken::new(token)));
+        self
+    }
+
+    /// Get the token from a provider consulted before each request, see [`TokenProvider`]
+    pub fn token_provider<P: TokenProvider + 'static>(mut self, token_provider: P) -> Self {
+        self.token = Some(Arc::new(token_provider));
+        self
+    }
+
+    /// Timeout of the connection phase
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Timeout of each read of the response
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Timeout of the whole request, from connection to the end of the response
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn user_agent<S: Into<String>>(mut self, user_agent: S) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Send the requests through a proxy
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxies.push(proxy);
+        self
+    }
+
+    /// Ignore the proxies set in the environment
+    pub fn no_proxy(mut self) -> Self {
+        self.no_proxy = true;
+        self
+    }
+
+    /// Trust an additional root certificate
+    pub fn add_root_certificate(mut self, certificate: Certificate) -> Self {
+        self.root_certificates.push(certificate);
+        self
+    }
+
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Add a middleware at the end of the chain, see [`Middleware`]
+    pub fn middleware<M: Middleware + 'static>(mut self, middleware: M) -> Self {
+        self.middlewares.push(Arc::new(middleware));
+        self
+    }
+
+    /// Enable the sudo mode automatically when an endpoint requires it, see [`SudoPolicy`]
+    pub fn sudo_policy(mut self, sudo_policy: SudoPolicy) -> Self {
+        self.sudo_policy = Some(sudo_policy);
+        self
+    }
+
+    /// Build the configured http client
+    pub fn build_http(&self) -> SimpleLoginResult<HttpClient> {
+        let mut builder = reqwest::Client::builder();
+
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.read_timeout {
+            builder = builder.read_timeout(timeout);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(user_agent) = &self.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+        if self.no_proxy {
+            builder = builder.no_proxy();
+        }
+        for proxy in &self.proxies {
+            builder = builder.proxy(proxy.clone());
+        }
+        for certificate in &self.root_certificates {
+            builder = builder.add_root_certificate(certificate.clone());
+        }
+
+        let client = builder.build().map_err(SimpleLoginError::BuildHttpClient)?;
+
+        let mut http = HttpClient::from(client)
+            .with_retry_policy(self.retry_policy.clone())
+            .with_middlewares(self.middlewares.clone());
+        if let Some(rate_limiter) = &self.rate_limiter {
+            http = http.with_rate_limiter(rate_limiter.clone());
+        }
+
+        Ok(http)
+    }
+
+    pub fn build(self) -> SimpleLoginResult<SimpleLoginClient> {
+        let url = Url::parse(&self.base_url)
+            .map_err(|_| SimpleLoginError::InvalidBaseUrl(self.base_url.clone()))?;
+
+        // The endpoints are appended to the base url, which can't have a query or a fragment
+        if url.query().is_some() || url.fragment().is_some() || url.cannot_be_a_base() {
+            return Err(SimpleLoginError::InvalidBaseUrl(self.base_url));
+        }
+
+        let hostname = match (url.scheme(), url.host_str()) {
+            ("http" | "https", Some(hostname)) => hostname.to_owned(),
+            _ => return Err(SimpleLoginError::InvalidBaseUrl(self.base_url)),
+        };
+
+        let http = self.build_http()?;
+
+        Ok(SimpleLoginClient {
+            http,
+            token: Arc::new(RwLock::new(self.token)),
+            base_url: url.as_str().trim_end_matches('/').into(),
+            hostname: hostname.into(),
+            sudo_policy: self.sudo_policy,
+        })
+    }
+}
@@ -1,11 +1,18 @@
+use std::sync::Arc;
+
+use reqwest::Method;
 use serde_json::json;
 
 use crate::{
-    account::{ApiKeyData, CookieTokenData, LoginData, MfaData, UserInfoData},
-    BaseHttpClient, MsgData, OkData, SimpleLoginError, SimpleLoginResult,
+    account::{
+        ApiKeyData, ApiKeyRotationData, CookieTokenData, LoginData, MfaData, StatsData,
+        UserInfoData,
+    },
+    redact, BaseHttpClient, MsgData, OkData, Secret, SimpleLoginClient, SimpleLoginError,
+    SimpleLoginResult, StaticToken,
 };
 
-use super::SimpleLogin;
+use super::{utils, SimpleLogin};
 
 pub struct EndpointsAccount<'a, S: SimpleLogin>(pub(crate) &'a S);
 
@@ -14,69 +21,48 @@ impl<S: SimpleLogin> EndpointsAccount<'_, S> {
     pub async fn login(
         self,
         email: &str,
-        password: &str,
+        password: &Secret,
         device: &str,
     ) -> SimpleLoginResult<LoginData> {
         let endpoint = "api/auth/login";
 
         let body = json!({
             "email": email,
-            "password": password,
+            "password": password.expose(),
             "device": device
         });
 
-        let response = self
-            .0
-            .get_http()
-            .post_public(&self.0.get_url(&endpoint), &(None, Some(&body)))
-            .await?;
-
-        serde_json::from_str::<LoginData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))
+        utils::request_public(self.0, endpoint, &(None, Some(&body))).await
     }
 
     /// 2FA authentication
     pub async fn mfa(
         self,
         mfa_token: &str,
-        mfa_key: &str,
+        mfa_key: &Secret,
         device: &str,
     ) -> SimpleLoginResult<MfaData> {
         let endpoint = "api/auth/mfa";
 
         let body = json!({
             "mfa_token": mfa_token,
-            "mfa_key": mfa_key,
+            "mfa_key": mfa_key.expose(),
             "device": device
         });
 
-        let response = self
-            .0
-            .get_http()
-            .post_public(&self.0.get_url(&endpoint), &(None, Some(&body)))
-            .await?;
-
-        serde_json::from_str::<MfaData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))
+        utils::request_public(self.0, endpoint, &(None, Some(&body))).await
     }
 
     /// Register a new account
-    pub async fn register(self, email: &str, password: &str) -> SimpleLoginResult<MsgData> {
+    pub async fn register(self, email: &str, password: &Secret) -> SimpleLoginResult<MsgData> {
         let endpoint = "api/auth/register";
 
         let body = json!({
             "email": email,
-            "password": password,
+            "password": password.expose(),
         });
 
-        let response = self
-            .0
-            .get_http()
-            .post_public(&self.0.get_url(&endpoint), &(None, Some(&body)))
-            .await?;
-
-        serde_json::from_str::<MsgData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))
+        utils::request_public(self.0, endpoint, &(None, Some(&body))).await
     }
 
     /// Activate new account
@@ -88,14 +74,7 @@ impl<S: SimpleLogin> EndpointsAccount<'_, S> {
             "code": code,
         });
 
-        let response = self
-            .0
-            .get_http()
-            .post_public(&self.0.get_url(&endpoint), &(None, Some(&body)))
-            .await?;
-
-        serde_json::from_str::<MsgData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))
+        utils::request_public(self.0, endpoint, &(None, Some(&body))).await
     }
 
     /// Request a new activation code
@@ -106,14 +85,7 @@ impl<S: SimpleLogin> EndpointsAccount<'_, S> {
             "email": email,
         });
 
-        let response = self
-            .0
-            .get_http()
-            .post_public(&self.0.get_url(&endpoint), &(None, Some(&body)))
-            .await?;
-
-        serde_json::from_str::<MsgData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))
+        utils::request_public(self.0, endpoint, &(None, Some(&body))).await
     }
 
     /// Request reset password link
@@ -124,90 +96,69 @@ impl<S: SimpleLogin> EndpointsAccount<'_, S> {
             "email": email,
         });
 
-        let response = self
-            .0
-            .get_http()
-            .post_public(&self.0.get_url(&endpoint), &(None, Some(&body)))
-            .await?;
-
-        serde_json::from_str::<OkData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))
+        utils::request_public(self.0, endpoint, &(None, Some(&body))).await
     }
 
     /// Get user's information
     pub async fn get_user_info(self) -> SimpleLoginResult<UserInfoData> {
         let endpoint = "api/user_info";
 
-        let response = self
-            .0
-            .get_http()
-            .get(
-                self.0.get_token(),
-                &self.0.get_url(&endpoint),
-                &(None, None),
-            )
-            .await?;
-
-        serde_json::from_str::<UserInfoData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))
+        utils::request(self.0, Method::GET, endpoint, &(None, None)).await
+    }
+
+    /// Get user's statistics
+    pub async fn stats(self) -> SimpleLoginResult<StatsData> {
+        let endpoint = "api/stats";
+
+        utils::request(self.0, Method::GET, endpoint, &(None, None)).await
     }
 
     /// Enable sudo mode
-    pub async fn sudo(self, password: &str) -> SimpleLoginResult<OkData> {
+    pub async fn sudo(self, password: &Secret) -> SimpleLoginResult<OkData> {
         let endpoint = "api/sudo";
 
         let body = json!({
-            "password": password,
+            "password": password.expose(),
         });
 
-        let response = self
-            .0
-            .get_http()
-            .patch(
-                self.0.get_token(),
-                &self.0.get_url(&endpoint),
-                &(None, Some(&body)),
-            )
-            .await?;
-
-        serde_json::from_str::<OkData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))
+        utils::request(self.0, Method::PATCH, endpoint, &(None, Some(&body))).await
     }
 
     /// Delete the current user
     pub async fn delete_user(self) -> SimpleLoginResult<OkData> {
         let endpoint = "api/user";
 
-        let response = self
-            .0
-            .get_http()
-            .delete(
-                self.0.get_token(),
-                &self.0.get_url(&endpoint),
-                &(None, None),
-            )
-            .await?;
-
-        serde_json::from_str::<OkData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))
+        utils::request(self.0, Method::DELETE, endpoint, &(None, None)).await
     }
 
     /// Get a one time use token to exchange it for a valid cookie
     pub async fn cookie_token(self) -> SimpleLoginResult<CookieTokenData> {
         let endpoint = "api/user/cookie_token";
 
-        let response = self
-            .0
-            .get_http()
-            .get(
-                self.0.get_token(),
-                &self.0.get_url(&endpoint),
-                &(None, None),
-            )
-            .await?;
-
-        serde_json::from_str::<CookieTokenData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))
+        utils::request(self.0, Method::GET, endpoint, &(None, None)).await
+    }
+
+    /// Get a url opening the dashboard already signed in, then redirecting to the `next` path
+    /// (e.g. `/dashboard/setting`). The url holds a one time use token
+    pub async fn web_login_url(self, next: Option<&str>) -> SimpleLoginResult<Secret> {
+        if let Some(next) = next {
+            if !next.starts_with('/') || next.starts_with("//") || next.contains('\\') {
+                return Err(SimpleLoginError::InvalidNextPath(next.to_owned()));
+            }
+        }
+
+        let client = self.0;
+        let token = self.cookie_token().await?.token;
+
+        let base_url = client.get_url("auth/api_to_cookie");
+        let mut url = reqwest::Url::parse(&base_url)
+            .map_err(|_| SimpleLoginError::InvalidBaseUrl(base_url))?;
+        url.query_pairs_mut().append_pair("token", token.expose());
+        if let Some(next) = next {
+            url.query_pairs_mut().append_pair("next", next);
+        }
+
+        Ok(Secret::new(String::from(url)))
     }
 
     /// Update user's information
@@ -244,18 +195,7 @@ impl<S: SimpleLogin> EndpointsAccount<'_, S> {
             serde_json::to_value(BodyB { name }).unwrap()
         };
 
-        let response = self
-            .0
-            .get_http()
-            .patch(
-                self.0.get_token(),
-                &self.0.get_url(&endpoint),
-                &(None, Some(&body)),
-            )
-            .await?;
-
-        serde_json::from_str::<UserInfoData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))
+        utils::request(self.0, Method::PATCH, endpoint, &(None, Some(&body))).await
     }
 
     /// Create a new API key
@@ -266,35 +206,62 @@ impl<S: SimpleLogin> EndpointsAccount<'_, S> {
             "device": device,
         });
 
-        let response = self
-            .0
-            .get_http()
-            .post(
-                self.0.get_token(),
-                &self.0.get_url(&endpoint),
-                &(None, Some(&body)),
-            )
-            .await?;
-
-        serde_json::from_str::<ApiKeyData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))
+        utils::request(self.0, Method::POST, endpoint, &(None, Some(&body))).await
     }
 
     /// Log out
     pub async fn logout(self) -> SimpleLoginResult<MsgData> {
         let endpoint = "api/logout";
 
-        let response = self
-            .0
-            .get_http()
-            .get(
-                self.0.get_token(),
-                &self.0.get_url(&endpoint),
-                &(None, None),
-            )
-            .await?;
-
-        serde_json::from_str::<MsgData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))
+        utils::request(self.0, Method::GET, endpoint, &(None, None)).await
+    }
+}
+
+impl<Http: BaseHttpClient> EndpointsAccount<'_, SimpleLoginClient<Http>> {
+    /// Replace the api key of the client by a new one.
+    ///
+    /// The new key is verified with `get_user_info` before logging out the old one,
+    /// the client is switched back to the old key if the verification fails.
+    ///
+    /// The token provider of the client is replaced by a [`crate::StaticToken`] holding the
+    /// new key. A [`crate::FileToken`], [`crate::EnvToken`] or [`crate::CommandToken`] would
+    /// still return the old key, which is logged out: store
+    /// [`ApiKeyRotationData::api_key`] there before setting the provider again
+    pub async fn rotate_api_key(self, device: &str) -> SimpleLoginResult<ApiKeyRotationData> {
+        let client = self.0;
+        let old_key = client.token().await?.ok_or(SimpleLoginError::TokenNotSet)?;
+
+        let new_key = client.account().create_api_key(device).await?.api_key;
+        let old_provider =
+            client.replace_token_provider(Some(Arc::new(StaticToken::new(new_key.clone()))));
+
+        let user = match client.account().get_user_info().await {
+            Ok(user) => user,
+            Err(error) => {
+                client.replace_token_provider(old_provider);
+                // Best effort, the new key may not be usable at all
+                let _ = client
+                    .clone_with_token(new_key.clone())
+                    .account()
+                    .logout()
+                    .await;
+                return Err(error);
+            }
+        };
+
+        let old_key_logged_out = client
+            .clone_with_token(old_key.as_ref())
+            .account()
+            .logout()
+            .await
+            .is_ok();
+
+        Ok(ApiKeyRotationData {
+            old_key: redact::redact_key(&old_key),
+            new_key: redact::redact_key(new_key.expose()),
+            api_key: new_key,
+            user,
+            old_key_logged_out,
+        })
     }
 }
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use reqwest::Method;
 use serde_json::json;
 
 use crate::alias::AliasActivityData;
@@ -9,15 +10,13 @@ use crate::alias::AliasToggleData;
 use crate::alias::OptionsData;
 use crate::DeletedData;
 use crate::OkData;
-use crate::SimpleLoginError;
 use crate::SimpleLoginResult;
 
 use crate::alias::VecAliasActivityData;
 use crate::alias::VecAliasContactData;
 use crate::alias::VecAliasData;
-use crate::BaseHttpClient;
 
-use super::SimpleLogin;
+use super::{utils, SimpleLogin};
 
 pub struct EndpointsAlias<'a, S: SimpleLogin>(pub(crate) &'a S);
 
@@ -26,18 +25,7 @@ impl<S: SimpleLogin> EndpointsAlias<'_, S> {
     pub async fn options(self) -> SimpleLoginResult<OptionsData> {
         let endpoint = "api/v5/alias/options";
 
-        let response = self
-            .0
-            .get_http()
-            .get(
-                self.0.get_token(),
-                &self.0.get_url(&endpoint),
-                &(None, None),
-            )
-            .await?;
-
-        serde_json::from_str::<OptionsData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))
+        utils::request(self.0, Method::GET, endpoint, &(None, None)).await
     }
 
     /// Create new alias
@@ -73,18 +61,7 @@ impl<S: SimpleLogin> EndpointsAlias<'_, S> {
         })
         .unwrap();
 
-        let response = self
-            .0
-            .get_http()
-            .post(
-                self.0.get_token(),
-                &self.0.get_url(&endpoint),
-                &(None, Some(&body)),
-            )
-            .await?;
-
-        serde_json::from_str::<AliasData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))
+        utils::request(self.0, Method::POST, endpoint, &(None, Some(&body))).await
     }
 
     /// Random an alias
@@ -114,18 +91,7 @@ impl<S: SimpleLogin> EndpointsAlias<'_, S> {
 
         let body = serde_json::to_value(Body { note }).unwrap();
 
-        let response = self
-            .0
-            .get_http()
-            .post(
-                self.0.get_token(),
-                &self.0.get_url(&endpoint),
-                &(query, Some(&body)),
-            )
-            .await?;
-
-        serde_json::from_str::<AliasData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))
+        utils::request(self.0, Method::POST, endpoint, &(query, Some(&body))).await
     }
 
     /// Get user's aliases
@@ -143,73 +109,78 @@ impl<S: SimpleLogin> EndpointsAlias<'_, S> {
             (filter.as_str(), "".to_owned()),
         ]);
 
-        let response = self
-            .0
-            .get_http()
-            .get(
-                self.0.get_token(),
-                &self.0.get_url(&endpoint),
-                &(Some(&query), None),
-            )
-            .await?;
-
-        Ok(serde_json::from_str::<VecAliasData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))?
-            .aliases)
+        let response: VecAliasData =
+            utils::request(self.0, Method::GET, endpoint, &(Some(&query), None)).await?;
+
+        Ok(response.aliases)
+    }
+
+    /// Search user's aliases by email, note or name
+    pub async fn search(self, query: &str, page_id: usize) -> SimpleLoginResult<Vec<AliasData>> {
+        self.list_with_options(
+            page_id,
+            AliasListOptions {
+                query: Some(query),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    /// Get user's aliases matching a filter and a search query.
+    ///
+    /// The api takes the search query in the body, so the request is a POST when it is set.
+    /// This POST only reads the aliases and is retried like a GET
+    pub async fn list_with_options(
+        self,
+        page_id: usize,
+        options: AliasListOptions<'_>,
+    ) -> SimpleLoginResult<Vec<AliasData>> {
+        let endpoint = "api/v2/aliases";
+
+        let mut query = HashMap::from([("page_id", page_id.to_string())]);
+
+        let filter = options.filter.map(|filter| filter.to_string());
+        if let Some(filter) = &filter {
+            query.insert(filter.as_str(), "".to_owned());
+        }
+
+        let body = options.query.map(|query| {
+            json!({
+                "query": query,
+            })
+        });
+
+        let method = match body {
+            Some(_) => Method::POST,
+            None => Method::GET,
+        };
+
+        let response: VecAliasData =
+            utils::request(self.0, method, endpoint, &(Some(&query), body.as_ref())).await?;
+
+        Ok(response.aliases)
     }
 
     /// Get alias information
     pub async fn get(self, alias_id: usize) -> SimpleLoginResult<AliasData> {
         let endpoint = &format!("api/aliases/{alias_id}");
 
-        let response = self
-            .0
-            .get_http()
-            .get(
-                self.0.get_token(),
-                &self.0.get_url(&endpoint),
-                &(None, None),
-            )
-            .await?;
-
-        serde_json::from_str::<AliasData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))
+        utils::request(self.0, Method::GET, endpoint, &(None, None)).await
     }
 
     /// Delete an alias
     pub async fn delete(self, alias_id: usize) -> SimpleLoginResult<DeletedData> {
         let endpoint = &format!("api/aliases/{alias_id}");
 
-        let response = self
-            .0
-            .get_http()
-            .delete(
-                self.0.get_token(),
-                &self.0.get_url(&endpoint),
-                &(None, None),
-            )
-            .await?;
-
-        serde_json::from_str::<DeletedData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))
+        utils::request(self.0, Method::DELETE, endpoint, &(None, None)).await
     }
 
     /// Enable/disable an alias
     pub async fn toggle(self, alias_id: usize) -> SimpleLoginResult<AliasToggleData> {
         let endpoint = &format!("api/aliases/{alias_id}/toggle");
 
-        let response = self
-            .0
-            .get_http()
-            .post(
-                self.0.get_token(),
-                &self.0.get_url(&endpoint),
-                &(None, None),
-            )
-            .await?;
-
-        serde_json::from_str::<AliasToggleData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))
+        utils::request(self.0, Method::POST, endpoint, &(None, None)).await
     }
 
     /// Get alias activities
@@ -222,19 +193,10 @@ impl<S: SimpleLogin> EndpointsAlias<'_, S> {
 
         let query = HashMap::from([("page_id", page_id.to_string())]);
 
-        let response = self
-            .0
-            .get_http()
-            .get(
-                self.0.get_token(),
-                &self.0.get_url(&endpoint),
-                &(Some(&query), None),
-            )
-            .await?;
-
-        Ok(serde_json::from_str::<VecAliasActivityData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))?
-            .activities)
+        let response: VecAliasActivityData =
+            utils::request(self.0, Method::GET, endpoint, &(Some(&query), None)).await?;
+
+        Ok(response.activities)
     }
 
     /// Update alias information
@@ -274,18 +236,7 @@ impl<S: SimpleLogin> EndpointsAlias<'_, S> {
         })
         .unwrap();
 
-        let response = self
-            .0
-            .get_http()
-            .patch(
-                self.0.get_token(),
-                &self.0.get_url(&endpoint),
-                &(None, Some(&body)),
-            )
-            .await?;
-
-        serde_json::from_str::<OkData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))
+        utils::request(self.0, Method::PATCH, endpoint, &(None, Some(&body))).await
     }
 
     /// Get alias contacts
@@ -298,19 +249,10 @@ impl<S: SimpleLogin> EndpointsAlias<'_, S> {
 
         let query = HashMap::from([("page_id", page_id.to_string())]);
 
-        let response = self
-            .0
-            .get_http()
-            .get(
-                self.0.get_token(),
-                &self.0.get_url(&endpoint),
-                &(Some(&query), None),
-            )
-            .await?;
-
-        Ok(serde_json::from_str::<VecAliasContactData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))?
-            .contacts)
+        let response: VecAliasContactData =
+            utils::request(self.0, Method::GET, endpoint, &(Some(&query), None)).await?;
+
+        Ok(response.contacts)
     }
 
     /// Create a new contact for an alias
@@ -325,21 +267,18 @@ impl<S: SimpleLogin> EndpointsAlias<'_, S> {
             "contact": contact,
         });
 
-        let response = self
-            .0
-            .get_http()
-            .post(
-                self.0.get_token(),
-                &self.0.get_url(&endpoint),
-                &(None, Some(&body)),
-            )
-            .await?;
-
-        serde_json::from_str::<AliasContactData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))
+        utils::request(self.0, Method::POST, endpoint, &(None, Some(&body))).await
     }
 }
 
+/// Options of [`EndpointsAlias::list_with_options`]
+#[derive(Default)]
+pub struct AliasListOptions<'a> {
+    pub filter: Option<AliasFilter>,
+    /// Search in the email, note and name of the aliases
+    pub query: Option<&'a str>,
+}
+
 pub enum AliasFilter {
     Pinned,
     Disabled,
@@ -1,8 +1,8 @@
-use crate::{
-    contact::ToggleContactData, BaseHttpClient, DeletedData, SimpleLoginError, SimpleLoginResult,
-};
+use reqwest::Method;
 
-use super::SimpleLogin;
+use crate::{contact::ToggleContactData, DeletedData, SimpleLoginResult};
+
+use super::{utils, SimpleLogin};
 
 pub struct EndpointsContact<'a, S: SimpleLogin>(pub(crate) &'a S);
 
@@ -11,35 +11,13 @@ impl<S: SimpleLogin> EndpointsContact<'_, S> {
     pub async fn delete(self, contact_id: usize) -> SimpleLoginResult<DeletedData> {
         let endpoint = &format!("api/contacts/{contact_id}");
 
-        let response = self
-            .0
-            .get_http()
-            .delete(
-                self.0.get_token(),
-                &self.0.get_url(&endpoint),
-                &(None, None),
-            )
-            .await?;
-
-        serde_json::from_str::<DeletedData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))
+        utils::request(self.0, Method::DELETE, endpoint, &(None, None)).await
     }
 
     /// Toggle a contact
     pub async fn toggle(self, contact_id: usize) -> SimpleLoginResult<ToggleContactData> {
         let endpoint = &format!("api/contacts/{contact_id}/toggle");
 
-        let response = self
-            .0
-            .get_http()
-            .post(
-                self.0.get_token(),
-                &self.0.get_url(&endpoint),
-                &(None, None),
-            )
-            .await?;
-
-        serde_json::from_str::<ToggleContactData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))
+        utils::request(self.0, Method::POST, endpoint, &(None, None)).await
     }
 }
@@ -1,12 +1,14 @@
+use reqwest::Method;
+
 use crate::{
     custom_domain::{
         CustomDomainData, DeCustomDomainData, DeletedAliasData, VecCustomDomainData,
         VecDeletedAliasData,
     },
-    BaseHttpClient, SimpleLoginError, SimpleLoginResult,
+    SimpleLoginResult,
 };
 
-use super::SimpleLogin;
+use super::{utils, SimpleLogin};
 
 pub struct EndpointsCustomDomain<'a, S: SimpleLogin>(pub(crate) &'a S);
 
@@ -15,19 +17,10 @@ impl<S: SimpleLogin> EndpointsCustomDomain<'_, S> {
     pub async fn list(self) -> SimpleLoginResult<Vec<CustomDomainData>> {
         let endpoint = "api/custom_domains";
 
-        let response = self
-            .0
-            .get_http()
-            .get(
-                self.0.get_token(),
-                &self.0.get_url(&endpoint),
-                &(None, None),
-            )
-            .await?;
+        let response: VecCustomDomainData =
+            utils::request(self.0, Method::GET, endpoint, &(None, None)).await?;
 
-        Ok(serde_json::from_str::<VecCustomDomainData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))?
-            .custom_domains)
+        Ok(response.custom_domains)
     }
 
     /// Update custom domain's information
@@ -61,37 +54,19 @@ impl<S: SimpleLogin> EndpointsCustomDomain<'_, S> {
         })
         .unwrap();
 
-        let response = self
-            .0
-            .get_http()
-            .patch(
-                self.0.get_token(),
-                &self.0.get_url(&endpoint),
-                &(None, Some(&body)),
-            )
-            .await?;
+        let response: DeCustomDomainData =
+            utils::request(self.0, Method::PATCH, endpoint, &(None, Some(&body))).await?;
 
-        Ok(serde_json::from_str::<DeCustomDomainData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))?
-            .custom_domain)
+        Ok(response.custom_domain)
     }
 
     /// Get deleted aliases of a custom domain
     pub async fn trash(self, custom_domain_id: usize) -> SimpleLoginResult<Vec<DeletedAliasData>> {
         let endpoint = &format!("api/custom_domains/{custom_domain_id}/trash");
 
-        let response = self
-            .0
-            .get_http()
-            .get(
-                self.0.get_token(),
-                &self.0.get_url(&endpoint),
-                &(None, None),
-            )
-            .await?;
+        let response: VecDeletedAliasData =
+            utils::request(self.0, Method::GET, endpoint, &(None, None)).await?;
 
-        Ok(serde_json::from_str::<VecDeletedAliasData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))?
-            .aliases)
+        Ok(response.aliases)
     }
 }
@@ -0,0 +1,195 @@
+use reqwest::Method;
+use serde_json::Value;
+
+use crate::{export::AliasExportData, ErrorContext, SimpleLoginError, SimpleLoginResult};
+
+use super::{utils, SimpleLogin};
+
+pub struct EndpointsExport<'a, S: SimpleLogin>(pub(crate) &'a S);
+
+impl<S: SimpleLogin> EndpointsExport<'_, S> {
+    /// Export all user's data (aliases, mailboxes, custom domains, contacts)
+    pub async fn data(self) -> SimpleLoginResult<Value> {
+        let endpoint = "api/export/data";
+
+        utils::request(self.0, Method::GET, endpoint, &(None, None)).await
+    }
+
+    /// Export all user's data as returned by the api, e.g. for archival
+    pub async fn data_raw(self) -> SimpleLoginResult<Vec<u8>> {
+        let endpoint = "api/export/data";
+
+        utils::send_raw(self.0, endpoint, &(None, None)).await
+    }
+
+    /// Export user's aliases
+    pub async fn aliases(self) -> SimpleLoginResult<Vec<AliasExportData>> {
+        let endpoint = "api/export/aliases";
+
+        let response = utils::send(self.0, Method::GET, endpoint, &(None, None)).await?;
+
+        parse_aliases(&response).map_err(|(row, error)| SimpleLoginError::DeserializeCsvResponse {
+            row,
+            error,
+            context: Box::new(
+                ErrorContext::new(Method::GET, &self.0.get_url(endpoint)).with_body(&response),
+            ),
+        })
+    }
+
+    /// Export user's aliases as the csv returned by the api, e.g. for archival
+    pub async fn aliases_raw(self) -> SimpleLoginResult<Vec<u8>> {
+        let endpoint = "api/export/aliases";
+
+        utils::send_raw(self.0, endpoint, &(None, None)).await
+    }
+}
+
+/// Parse the aliases csv (`alias,note,enabled,mailboxes`), the columns are found by their header
+fn parse_aliases(body: &str) -> Result<Vec<AliasExportData>, (usize, String)> {
+    let mut records = parse_csv(body)?.into_iter();
+
+    let header = records.next().ok_or((1, "missing header".to_owned()))?;
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|column| column == name)
+            .ok_or((1, format!("missing column '{name}'")))
+    };
+    let (email, note, enabled, mailboxes) = (
+        column("alias")?,
+        column("note")?,
+        column("enabled")?,
+        column("mailboxes")?,
+    );
+
+    records
+        .enumerate()
+        .map(|(index, record)| {
+            let row = index + 2;
+            let field = |column: usize| {
+                record
+                    .get(column)
+                    .map(String::as_str)
+                    .ok_or((row, format!("missing field {}", column + 1)))
+            };
+
+            let enabled = match field(enabled)? {
+                "True" | "true" => true,
+                "False" | "false" => false,
+                value => return Err((row, format!("invalid boolean '{value}'"))),
+            };
+            let note = Some(field(note)?)
+                .filter(|note| !note.is_empty())
+                .map(str::to_owned);
+
+            Ok(AliasExportData {
+                email: field(email)?.to_owned(),
+                note,
+                enabled,
+                mailboxes: field(mailboxes)?
+                    .split_whitespace()
+                    .map(str::to_owned)
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+/// Parse a RFC 4180 csv, skipping the empty lines and returning the error with its row
+fn parse_csv(body: &str) -> Result<Vec<Vec<String>>, (usize, String)> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+
+    let mut chars = body.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => record.push(std::mem::take(&mut field)),
+            (false, '\r') if chars.peek() == Some(&'\n') => {}
+            (false, '\n') if record.is_empty() && field.is_empty() => {}
+            (false, '\n') => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            (false, c) => field.push(c),
+        }
+    }
+
+    if quoted {
+        return Err((records.len() + 1, "unterminated quoted field".to_owned()));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_quoted_fields() {
+        let records = parse_csv("a,\"b, c\",\"say \"\"hi\"\"\"\r\nd,,e\r\n").unwrap();
+
+        assert_eq!(
+            records,
+            [vec!["a", "b, c", "say \"hi\""], vec!["d", "", "e"]]
+        );
+    }
+
+    #[test]
+    fn parse_skips_empty_lines() {
+        let records = parse_csv("a,b\n\nc,d\r\n\r\n").unwrap();
+
+        assert_eq!(records, [vec!["a", "b"], vec!["c", "d"]]);
+    }
+
+    #[test]
+    fn parse_unterminated_quote() {
+        assert_eq!(
+            parse_csv("a,b\n\"c,d\n"),
+            Err((2, "unterminated quoted field".to_owned()))
+        );
+    }
+
+    #[test]
+    fn parse_aliases_csv() {
+        let body = "alias,note,enabled,mailboxes\r\n\
+            a@simplelogin.co,,True,m1@example.com\r\n\
+            \r\n\
+            b@simplelogin.co,\"shop, \"\"online\"\"\",False,m1@example.com m2@example.com\r\n";
+
+        let aliases = parse_aliases(body).unwrap();
+
+        assert_eq!(aliases.len(), 2);
+        assert_eq!(aliases[0].email, "a@simplelogin.co");
+        assert_eq!(aliases[0].note, None);
+        assert!(aliases[0].enabled);
+        assert_eq!(aliases[0].mailboxes, ["m1@example.com"]);
+        assert_eq!(aliases[1].note.as_deref(), Some("shop, \"online\""));
+        assert!(!aliases[1].enabled);
+        assert_eq!(aliases[1].mailboxes, ["m1@example.com", "m2@example.com"]);
+    }
+
+    #[test]
+    fn parse_aliases_invalid_boolean() {
+        let body = "alias,note,enabled,mailboxes\na@simplelogin.co,,yes,m1@example.com\n";
+
+        assert_eq!(
+            parse_aliases(body).unwrap_err(),
+            (2, "invalid boolean 'yes'".to_owned())
+        );
+    }
+}
@@ -1,15 +1,33 @@
+use reqwest::Method;
 use serde_json::json;
 
 use crate::{
-    mailbox::MailboxData, BaseHttpClient, DeletedData, SimpleLoginError, SimpleLoginResult,
-    UpdatedData,
+    mailbox::{MailboxData, RetiredMailboxData, VecMailboxData},
+    DeletedData, SimpleLoginError, SimpleLoginResult, UpdatedData,
 };
 
-use super::SimpleLogin;
+use super::{utils, SimpleLogin};
 
 pub struct EndpointsMailbox<'a, S: SimpleLogin>(pub(crate) &'a S);
 
 impl<S: SimpleLogin> EndpointsMailbox<'_, S> {
+    /// Get mailboxes
+    pub async fn list(self) -> SimpleLoginResult<Vec<MailboxData>> {
+        let endpoint = "api/v2/mailboxes";
+
+        let response: VecMailboxData =
+            utils::request(self.0, Method::GET, endpoint, &(None, None)).await?;
+
+        Ok(response.mailboxes)
+    }
+
+    /// Get the default mailbox
+    pub async fn get_default(self) -> SimpleLoginResult<Option<MailboxData>> {
+        let mailboxes = self.list().await?;
+
+        Ok(mailboxes.into_iter().find(|mailbox| mailbox.default))
+    }
+
     /// Create a new mailbox
     pub async fn create(self, email: &str) -> SimpleLoginResult<MailboxData> {
         let endpoint = "api/mailboxes";
@@ -18,36 +36,63 @@ impl<S: SimpleLogin> EndpointsMailbox<'_, S> {
             "email": email,
         });
 
-        let response = self
-            .0
-            .get_http()
-            .post(
-                self.0.get_token(),
-                &self.0.get_url(&endpoint),
-                &(None, Some(&body)),
-            )
-            .await?;
-
-        serde_json::from_str::<MailboxData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))
+        utils::request(self.0, Method::POST, endpoint, &(None, Some(&body))).await
     }
 
-    /// Delete a mailbox
-    pub async fn delete(self, mailbox_id: usize) -> SimpleLoginResult<DeletedData> {
+    /// Delete a mailbox. Its aliases are moved to `transfer_aliases_to`, or deleted if `None`
+    pub async fn delete(
+        self,
+        mailbox_id: usize,
+        transfer_aliases_to: Option<usize>,
+    ) -> SimpleLoginResult<DeletedData> {
         let endpoint = &format!("api/mailboxes/{mailbox_id}");
 
-        let response = self
-            .0
-            .get_http()
-            .delete(
-                self.0.get_token(),
-                &self.0.get_url(&endpoint),
-                &(None, None),
-            )
-            .await?;
-
-        serde_json::from_str::<DeletedData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))
+        let body = transfer_aliases_to.map(|transfer_aliases_to| {
+            json!({
+                "transfer_aliases_to": transfer_aliases_to,
+            })
+        });
+
+        utils::request(self.0, Method::DELETE, endpoint, &(None, body.as_ref())).await
+    }
+
+    /// Delete a mailbox after checking that the target receiving its aliases is verified
+    pub async fn retire(
+        self,
+        mailbox_id: usize,
+        target_id: usize,
+    ) -> SimpleLoginResult<RetiredMailboxData> {
+        if mailbox_id == target_id {
+            return Err(SimpleLoginError::RetireMailbox(
+                "the target is the mailbox to retire".to_owned(),
+            ));
+        }
+
+        let mailboxes = EndpointsMailbox(self.0).list().await?;
+        let find = |id: usize| {
+            mailboxes
+                .iter()
+                .find(|mailbox| mailbox.id == id)
+                .cloned()
+                .ok_or_else(|| SimpleLoginError::RetireMailbox(format!("mailbox {id} not found")))
+        };
+        let mailbox = find(mailbox_id)?;
+        let target = find(target_id)?;
+
+        if !target.verified {
+            return Err(SimpleLoginError::RetireMailbox(format!(
+                "the target mailbox '{}' is not verified",
+                target.email
+            )));
+        }
+
+        self.delete(mailbox_id, Some(target_id)).await?;
+
+        Ok(RetiredMailboxData {
+            transferred_aliases: mailbox.nb_alias,
+            mailbox,
+            target,
+        })
     }
 
     /// Update a mailbox
@@ -77,17 +122,6 @@ impl<S: SimpleLogin> EndpointsMailbox<'_, S> {
         })
         .unwrap();
 
-        let response = self
-            .0
-            .get_http()
-            .put(
-                self.0.get_token(),
-                &self.0.get_url(&endpoint),
-                &(None, Some(&body)),
-            )
-            .await?;
-
-        serde_json::from_str::<UpdatedData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))
+        utils::request(self.0, Method::PUT, endpoint, &(None, Some(&body))).await
     }
 }
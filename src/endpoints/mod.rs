@@ -1,66 +1,212 @@
+use std::sync::{Arc, RwLock};
+
 use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
 
 pub use account::*;
 pub use alias::*;
 pub use contact::*;
 pub use custom_domain::*;
+pub use export::*;
 pub use mailbox::*;
 pub use notification::*;
 pub use setting::*;
+pub use utils::parse_error_from_response;
 
-use crate::{SimpleLoginError, SimpleLoginResult};
+use crate::{
+    AuthFlow, ErrorContext, Secret, SimpleLoginError, SimpleLoginResult, StaticToken, SudoPolicy,
+    TokenProvider,
+};
 
 mod account;
 mod alias;
 mod contact;
 mod custom_domain;
+mod export;
 mod mailbox;
 mod notification;
 mod setting;
 mod utils;
 
 pub trait SimpleLogin {
-    fn get_http(&self) -> &HttpClient;
-    fn get_token(&self) -> Option<&str>;
+    type Http: BaseHttpClient;
+
+    fn get_http(&self) -> &Self::Http;
+    fn get_token_provider(&self) -> Option<Arc<dyn TokenProvider>>;
     fn get_url<S: AsRef<str> + std::fmt::Display>(&self, endpoint: S) -> String;
     fn get_hostname(&self) -> &str;
+    fn get_sudo_policy(&self) -> Option<&SudoPolicy>;
 }
 
-pub struct SimpleLoginClient<'a> {
-    pub http: HttpClient,
-    pub hostname: &'a str,
-    pub token: Option<&'a str>,
+/// Client of the api.
+///
+/// The client owns its configuration and is cheap to clone, so it can be stored in
+/// long-lived state or moved into spawned tasks. Clones share the same token.
+#[derive(Debug, Clone)]
+pub struct SimpleLoginClient<Http: BaseHttpClient = HttpClient> {
+    pub http: Http,
+    pub(crate) token: Arc<RwLock<Option<Arc<dyn TokenProvider>>>>,
+    pub(crate) base_url: Arc<str>,
+    pub(crate) hostname: Arc<str>,
+    pub(crate) sudo_policy: Option<SudoPolicy>,
 }
 
-impl SimpleLogin for SimpleLoginClient<'_> {
-    fn get_http(&self) -> &HttpClient {
+impl<Http: BaseHttpClient> SimpleLogin for SimpleLoginClient<Http> {
+    type Http = Http;
+
+    fn get_http(&self) -> &Http {
         &self.http
     }
 
-    fn get_token(&self) -> Option<&str> {
-        self.token
+    fn get_token_provider(&self) -> Option<Arc<dyn TokenProvider>> {
+        self.token.read().unwrap_or_else(|e| e.into_inner()).clone()
     }
 
     fn get_url<S: AsRef<str> + std::fmt::Display>(&self, endpoint: S) -> String {
-        format!("https://{}/{}", self.hostname, endpoint)
+        format!("{}/{}", self.base_url, endpoint)
     }
 
     fn get_hostname(&self) -> &str {
         &self.hostname
     }
+
+    fn get_sudo_policy(&self) -> Option<&SudoPolicy> {
+        self.sudo_policy.as_ref()
+    }
+}
+
+impl SimpleLoginClient {
+    pub fn new(hostname: &str) -> Self {
+        Self::with_http(hostname, HttpClient::default())
+    }
+
+    /// Configure the client (base url, timeouts, proxies, ...)
+    pub fn builder<S: Into<String>>(base_url: S) -> SimpleLoginClientBuilder {
+        SimpleLoginClientBuilder::new(base_url)
+    }
+
+    /// Set the retry policy used on rate limits and transient failures
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.http = self.http.with_retry_policy(retry_policy);
+        self
+    }
+
+    /// Throttle the requests to the given budget, see [`RateLimiter`]
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.http = self.http.with_rate_limiter(rate_limiter);
+        self
+    }
+
+    /// Add a middleware at the end of the chain, see [`Middleware`]
+    pub fn with_middleware<M: Middleware + 'static>(mut self, middleware: M) -> Self {
+        self.http = self.http.with_middleware(middleware);
+        self
+    }
 }
 
-impl<'a> SimpleLoginClient<'a> {
-    pub fn new(hostname: &'a str) -> Self {
+impl<Http: BaseHttpClient> SimpleLoginClient<Http> {
+    /// Create a client using a custom http transport.
+    ///
+    /// The retry policy, the rate limiter and the middlewares only apply to [`HttpClient`],
+    /// see [`BaseHttpClient`]
+    pub fn with_http(hostname: &str, http: Http) -> Self {
+        Self {
+            http,
+            token: Default::default(),
+            base_url: format!("https://{hostname}").into(),
+            hostname: hostname.into(),
+            sudo_policy: None,
+        }
+    }
+
+    /// Set the token used to authenticate the requests
+    pub fn with_token<S: Into<Secret>>(self, token: S) -> Self {
+        self.set_token(token);
+        self
+    }
+
+    /// Clone the client with its own token, which is not shared with this client
+    pub fn clone_with_token<S: Into<Secret>>(&self, token: S) -> Self {
         Self {
-            http: HttpClient {
-                client: reqwest::Client::new(),
-            },
-            hostname,
-            token: None,
+            http: self.http.clone(),
+            token: Arc::new(RwLock::new(Some(Arc::new(StaticToken::new(token))))),
+            base_url: self.base_url.clone(),
+            hostname: self.hostname.clone(),
+            sudo_policy: self.sudo_policy.clone(),
         }
     }
 
+    /// Enable the sudo mode automatically when an endpoint requires it, see [`SudoPolicy`]
+    pub fn with_sudo_policy(mut self, sudo_policy: SudoPolicy) -> Self {
+        self.sudo_policy = Some(sudo_policy);
+        self
+    }
+
+    /// Log in, handling the 2FA, see [`AuthFlow`]
+    pub fn auth_flow<'a>(&'a self, device: &'a str) -> AuthFlow<'a, Http> {
+        AuthFlow::new(self, device)
+    }
+
+    /// Call an endpoint that is not wrapped by this crate, e.g. `request(Method::GET, "api/new_endpoint", None, None)`.
+    ///
+    /// The token, the url and the errors are handled like for the other endpoints
+    pub async fn request<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        endpoint: &str,
+        query: Option<&Query<'_>>,
+        body: Option<&Value>,
+    ) -> SimpleLoginResult<T> {
+        utils::request(self, method, endpoint, &(query, body)).await
+    }
+
+    /// Url of the instance, without trailing slash
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Current token, asked to the token provider
+    pub async fn token(&self) -> SimpleLoginResult<Option<Arc<str>>> {
+        match self.get_token_provider() {
+            Some(token_provider) => token_provider.token().await,
+            None => Ok(None),
+        }
+    }
+
+    /// Replace the token at runtime (e.g. after a login or a key rotation).
+    /// The new token is used by every clone of this client
+    pub fn set_token<S: Into<Secret>>(&self, token: S) {
+        self.set_token_provider(StaticToken::new(token));
+    }
+
+    /// Get the token from a provider consulted before each request, see [`TokenProvider`]
+    pub fn with_token_provider<P: TokenProvider + 'static>(self, token_provider: P) -> Self {
+        self.set_token_provider(token_provider);
+        self
+    }
+
+    /// Replace the token provider at runtime, it is used by every clone of this client
+    pub fn set_token_provider<P: TokenProvider + 'static>(&self, token_provider: P) {
+        *self.token.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(token_provider));
+    }
+
+    /// Swap the token provider, returning the previous one
+    pub(crate) fn replace_token_provider(
+        &self,
+        token_provider: Option<Arc<dyn TokenProvider>>,
+    ) -> Option<Arc<dyn TokenProvider>> {
+        std::mem::replace(
+            &mut *self.token.write().unwrap_or_else(|e| e.into_inner()),
+            token_provider,
+        )
+    }
+
+    /// Remove the token, the following requests are not authenticated
+    pub fn clear_token(&self) {
+        *self.token.write().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
     pub fn account(&self) -> EndpointsAccount<'_, Self> {
         EndpointsAccount(self)
     }
@@ -88,51 +234,251 @@ impl<'a> SimpleLoginClient<'a> {
     pub fn setting(&self) -> EndpointsSetting<'_, Self> {
         EndpointsSetting(self)
     }
+
+    pub fn export(&self) -> EndpointsExport<'_, Self> {
+        EndpointsExport(self)
+    }
 }
 
-use crate::{BaseHttpClient, Payload};
+use crate::{
+    BaseHttpClient, Headers, Middleware, Middlewares, Payload, Query, RateLimiter, RequestParts,
+    ResponseParts, RetryPolicy, SimpleLoginClientBuilder,
+};
+
+use reqwest::Method;
 
-use reqwest::{Method, RequestBuilder};
+#[cfg(feature = "tracing")]
+use crate::{endpoint_path, redact};
 
 #[derive(Default, Debug, Clone)]
 pub struct HttpClient {
     client: reqwest::Client,
+    retry_policy: RetryPolicy,
+    rate_limiter: Option<RateLimiter>,
+    middlewares: Middlewares,
+}
+
+impl From<reqwest::Client> for HttpClient {
+    fn from(client: reqwest::Client) -> Self {
+        Self {
+            client,
+            ..Default::default()
+        }
+    }
 }
 
 impl HttpClient {
-    async fn request<D>(
+    /// Set the retry policy used on rate limits and transient failures
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    /// Throttle the requests with a rate limiter, shared with every clone of this client
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    pub fn rate_limiter(&self) -> Option<&RateLimiter> {
+        self.rate_limiter.as_ref()
+    }
+
+    /// Add a middleware at the end of the chain, see [`Middleware`]
+    pub fn with_middleware<M: Middleware + 'static>(mut self, middleware: M) -> Self {
+        self.middlewares.push(Arc::new(middleware));
+        self
+    }
+
+    pub(crate) fn with_middlewares(mut self, middlewares: Middlewares) -> Self {
+        self.middlewares = middlewares;
+        self
+    }
+
+    async fn request(
         &self,
         token: Option<&str>,
         method: Method,
         url: &str,
-        add_data: D,
-    ) -> SimpleLoginResult<String>
-    where
-        D: Fn(RequestBuilder) -> RequestBuilder,
-    {
-        let mut request = self.client.request(method.clone(), url);
+        payload: &Payload<'_>,
+    ) -> SimpleLoginResult<Vec<u8>> {
+        #[cfg(feature = "tracing")]
+        let endpoint = endpoint_path(url);
+
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!(
+            "simplelogin_request",
+            method = %method,
+            endpoint = %endpoint,
+            status = tracing::field::Empty,
+            attempts = tracing::field::Empty,
+            latency_ms = tracing::field::Empty,
+        );
+
+        let request = self.send(token, method, url, payload);
+
+        #[cfg(feature = "tracing")]
+        let request = tracing::Instrument::instrument(request, span);
+
+        request.await
+    }
+
+    async fn send(
+        &self,
+        token: Option<&str>,
+        method: Method,
+        url: &str,
+        payload: &Payload<'_>,
+    ) -> SimpleLoginResult<Vec<u8>> {
+        #[cfg(feature = "tracing")]
+        let start = std::time::Instant::now();
+
+        let mut parts = RequestParts {
+            method,
+            url: url.to_owned(),
+            headers: Headers::new(),
+            query: payload
+                .0
+                .map(|query| {
+                    query
+                        .iter()
+                        .map(|(key, value)| (key.to_string(), value.clone()))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            json: payload.1.cloned(),
+        };
 
         if let Some(token) = token {
             // let token = self.token.ok_or(SimpleLoginError::TokenNotSet)?;
-            request = request.header("Authentication", token);
+            parts
+                .headers
+                .insert("Authentication".to_owned(), token.to_owned());
         }
 
-        request = add_data(request);
+        self.middlewares.on_request(&mut parts);
 
-        let response = request
-            .send()
-            .await
-            .map_err(|e| SimpleLoginError::Request(e, url.into()))?;
-
-        let status = response.status();
-        let response = response
-            .text()
-            .await
-            .map_err(|e| SimpleLoginError::Request(e, url.into()))?;
-
-        utils::parse_error_from_response(&response, status, url).await?;
+        #[cfg(feature = "tracing")]
+        if let Some(body) = &parts.json {
+            tracing::trace!(body = %redact::redact_json(body), "request payload");
+        }
 
-        Ok(response)
+        let method = &parts.method;
+        let url = parts.url.as_str();
+        let mut attempt = 1;
+
+        loop {
+            let mut request = self.client.request(method.clone(), url);
+
+            for (name, value) in &parts.headers {
+                request = request.header(name, value);
+            }
+            if !parts.query.is_empty() {
+                request = request.query(&parts.query);
+            }
+            if let Some(json) = &parts.json {
+                request = request.json(json);
+            }
+
+            if let Some(rate_limiter) = &self.rate_limiter {
+                rate_limiter.acquire().await;
+            }
+
+            let can_retry = attempt < self.retry_policy.max_attempts;
+
+            let response = match request.send().await {
+                Ok(response) => response,
+                Err(e) if can_retry && self.retry_policy.should_retry_error(method, url, &e) => {
+                    let delay = self.retry_policy.backoff(attempt);
+
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(
+                        attempt,
+                        error = %e,
+                        delay_ms = delay.as_millis() as u64,
+                        "request failed, retrying"
+                    );
+
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                    continue;
+                }
+                Err(e) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(attempt, error = %e, "request failed");
+
+                    return Err(SimpleLoginError::Request {
+                        source: e,
+                        context: Box::new(ErrorContext::new(method.clone(), url)),
+                    });
+                }
+            };
+
+            let status = response.status();
+            let headers = response.headers().clone();
+
+            let delay =
+                match can_retry && self.retry_policy.should_retry_status(method, url, status) {
+                    true => self.retry_policy.delay(attempt, &headers),
+                    false => None,
+                };
+            if let Some(delay) = delay {
+                let body = response.bytes().await.unwrap_or_default();
+                self.middlewares.on_response(
+                    &parts,
+                    &ResponseParts::new(status, &headers, &String::from_utf8_lossy(&body)),
+                );
+
+                #[cfg(feature = "tracing")]
+                tracing::warn!(
+                    attempt,
+                    status = status.as_u16(),
+                    delay_ms = delay.as_millis() as u64,
+                    "request rejected, retrying"
+                );
+
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+                continue;
+            }
+
+            let body = response
+                .bytes()
+                .await
+                .map_err(|e| SimpleLoginError::Request {
+                    source: e,
+                    context: Box::new(ErrorContext::new(method.clone(), url).with_status(status)),
+                })?;
+            let response = String::from_utf8_lossy(&body);
+
+            #[cfg(feature = "tracing")]
+            {
+                let latency_ms = start.elapsed().as_millis() as u64;
+                let span = tracing::Span::current();
+                span.record("status", status.as_u16());
+                span.record("attempts", attempt);
+                span.record("latency_ms", latency_ms);
+
+                tracing::debug!(
+                    status = status.as_u16(),
+                    attempt,
+                    latency_ms,
+                    "response received"
+                );
+                tracing::trace!(body = %redact::redact_body(&response), "response body");
+            }
+
+            self.middlewares
+                .on_response(&parts, &ResponseParts::new(status, &headers, &response));
+
+            parse_error_from_response(&response, status, &headers, method, url)?;
+
+            return Ok(body.into());
+        }
     }
 }
 
@@ -145,8 +491,19 @@ impl BaseHttpClient for HttpClient {
         url: &str,
         payload: &Payload,
     ) -> SimpleLoginResult<String> {
-        self.request(token, Method::GET, url, |req| set_payload(req, payload))
+        self.request(token, Method::GET, url, payload)
             .await
+            .map(into_text)
+    }
+
+    #[inline]
+    async fn get_bytes(
+        &self,
+        token: Option<&str>,
+        url: &str,
+        payload: &Payload,
+    ) -> SimpleLoginResult<Vec<u8>> {
+        self.request(token, Method::GET, url, payload).await
     }
 
     #[inline]
@@ -156,14 +513,16 @@ impl BaseHttpClient for HttpClient {
         url: &str,
         payload: &Payload,
     ) -> SimpleLoginResult<String> {
-        self.request(token, Method::POST, url, |req| set_payload(req, payload))
+        self.request(token, Method::POST, url, payload)
             .await
+            .map(into_text)
     }
 
     #[inline]
     async fn post_public(&self, url: &str, payload: &Payload) -> SimpleLoginResult<String> {
-        self.request(None, Method::POST, url, |req| set_payload(req, payload))
+        self.request(None, Method::POST, url, payload)
             .await
+            .map(into_text)
     }
 
     #[inline]
@@ -173,8 +532,9 @@ impl BaseHttpClient for HttpClient {
         url: &str,
         payload: &Payload,
     ) -> SimpleLoginResult<String> {
-        self.request(token, Method::PUT, url, |req| set_payload(req, payload))
+        self.request(token, Method::PUT, url, payload)
             .await
+            .map(into_text)
     }
 
     #[inline]
@@ -184,8 +544,9 @@ impl BaseHttpClient for HttpClient {
         url: &str,
         payload: &Payload,
     ) -> SimpleLoginResult<String> {
-        self.request(token, Method::PATCH, url, |req| set_payload(req, payload))
+        self.request(token, Method::PATCH, url, payload)
             .await
+            .map(into_text)
     }
 
     #[inline]
@@ -195,20 +556,14 @@ impl BaseHttpClient for HttpClient {
         url: &str,
         payload: &Payload,
     ) -> SimpleLoginResult<String> {
-        self.request(token, Method::DELETE, url, |req| set_payload(req, payload))
+        self.request(token, Method::DELETE, url, payload)
             .await
+            .map(into_text)
     }
 }
 
-#[inline]
-fn set_payload(req: RequestBuilder, payload: &Payload) -> RequestBuilder {
-    let req = match payload.0 {
-        Some(query) => req.query(query),
-        None => req,
-    };
-    let req = match payload.1 {
-        Some(json) => req.json(json),
-        None => req,
-    };
-    req
+/// Decode a body as utf-8, replacing the invalid sequences like `reqwest::Response::text`
+fn into_text(body: Vec<u8>) -> String {
+    String::from_utf8(body)
+        .unwrap_or_else(|error| String::from_utf8_lossy(error.as_bytes()).into_owned())
 }
@@ -1,9 +1,11 @@
+use reqwest::Method;
+
 use crate::{
     setting::{SettingData, SettingDomainData},
-    BaseHttpClient, SimpleLoginError, SimpleLoginResult,
+    SimpleLoginResult,
 };
 
-use super::SimpleLogin;
+use super::{utils, SimpleLogin};
 
 pub struct EndpointsSetting<'a, S: SimpleLogin>(pub(crate) &'a S);
 
@@ -12,18 +14,7 @@ impl<S: SimpleLogin> EndpointsSetting<'_, S> {
     pub async fn get(self) -> SimpleLoginResult<SettingData> {
         let endpoint = "api/setting";
 
-        let response = self
-            .0
-            .get_http()
-            .get(
-                self.0.get_token(),
-                &self.0.get_url(&endpoint),
-                &(None, None),
-            )
-            .await?;
-
-        serde_json::from_str::<SettingData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))
+        utils::request(self.0, Method::GET, endpoint, &(None, None)).await
     }
 
     /// Update user's settings
@@ -60,36 +51,14 @@ impl<S: SimpleLogin> EndpointsSetting<'_, S> {
         })
         .unwrap();
 
-        let response = self
-            .0
-            .get_http()
-            .patch(
-                self.0.get_token(),
-                &self.0.get_url(&endpoint),
-                &(None, Some(&body)),
-            )
-            .await?;
-
-        serde_json::from_str::<SettingData>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))
+        utils::request(self.0, Method::PATCH, endpoint, &(None, Some(&body))).await
     }
 
     /// Get domains that user can use to create random alias
     pub async fn domains(self) -> SimpleLoginResult<Vec<SettingDomainData>> {
         let endpoint = "api/v2/setting/domains";
 
-        let response = self
-            .0
-            .get_http()
-            .get(
-                self.0.get_token(),
-                &self.0.get_url(&endpoint),
-                &(None, None),
-            )
-            .await?;
-
-        serde_json::from_str::<Vec<SettingDomainData>>(&response)
-            .map_err(|e| SimpleLoginError::DeserializeApiResponse(e))
+        utils::request(self.0, Method::GET, endpoint, &(None, None)).await
     }
 }
 
@@ -1,30 +1,216 @@
-use reqwest::StatusCode;
+use std::sync::Arc;
+use std::time::Instant;
 
-use crate::{ErrorData, SimpleLoginError, SimpleLoginResult};
+use reqwest::{header::HeaderMap, Method, StatusCode};
+use serde::de::DeserializeOwned;
+use serde_json::json;
 
-pub(crate) async fn parse_error_from_response<S, D>(
+use crate::{
+    parse_retry_after, BaseHttpClient, ErrorContext, ErrorData, Payload, SimpleLoginError,
+    SimpleLoginResult,
+};
+
+use super::SimpleLogin;
+
+/// Send a request to an endpoint of the api with the client's token.
+///
+/// With a [`crate::SudoPolicy`], a request failing with `NeedSudo` is replayed once
+/// after enabling the sudo mode
+pub(crate) async fn send<S: SimpleLogin>(
+    client: &S,
+    method: Method,
+    endpoint: &str,
+    payload: &Payload<'_>,
+) -> SimpleLoginResult<String> {
+    let token = token(client).await?;
+    let token = token.as_deref();
+
+    let sent_at = Instant::now();
+    let response = dispatch(client, token, method.clone(), endpoint, payload).await;
+
+    let sudo_policy = match (client.get_sudo_policy(), &response) {
+        (Some(sudo_policy), Err(SimpleLoginError::NeedSudo { .. })) => sudo_policy,
+        _ => return response,
+    };
+
+    let escalation = sudo_policy
+        .escalate(token, sent_at, |password| async move {
+            let body = json!({
+                "password": password.expose(),
+            });
+
+            dispatch(
+                client,
+                token,
+                Method::PATCH,
+                "api/sudo",
+                &(None, Some(&body)),
+            )
+            .await
+            .map(|_| ())
+        })
+        .await;
+
+    match escalation {
+        Some(Ok(())) => dispatch(client, token, method, endpoint, payload).await,
+        Some(Err(error)) => Err(error),
+        None => response,
+    }
+}
+
+/// Send a GET request to an endpoint of the api with the client's token, returning
+/// the body as received (e.g. an export to archive)
+pub(crate) async fn send_raw<S: SimpleLogin>(
+    client: &S,
+    endpoint: &str,
+    payload: &Payload<'_>,
+) -> SimpleLoginResult<Vec<u8>> {
+    let token = token(client).await?;
+
+    client
+        .get_http()
+        .get_bytes(token.as_deref(), &client.get_url(endpoint), payload)
+        .await
+}
+
+async fn token<S: SimpleLogin>(client: &S) -> SimpleLoginResult<Option<Arc<str>>> {
+    match client.get_token_provider() {
+        Some(token_provider) => token_provider.token().await,
+        None => Ok(None),
+    }
+}
+
+async fn dispatch<S: SimpleLogin>(
+    client: &S,
+    token: Option<&str>,
+    method: Method,
+    endpoint: &str,
+    payload: &Payload<'_>,
+) -> SimpleLoginResult<String> {
+    let http = client.get_http();
+    let url = client.get_url(endpoint);
+
+    match method {
+        Method::GET => http.get(token, &url, payload).await,
+        Method::POST => http.post(token, &url, payload).await,
+        Method::PUT => http.put(token, &url, payload).await,
+        Method::PATCH => http.patch(token, &url, payload).await,
+        Method::DELETE => http.delete(token, &url, payload).await,
+        method => Err(SimpleLoginError::UnsupportedMethod(method)),
+    }
+}
+
+/// Send a request to an endpoint of the api and deserialize the response
+pub(crate) async fn request<S: SimpleLogin, T: DeserializeOwned>(
+    client: &S,
+    method: Method,
+    endpoint: &str,
+    payload: &Payload<'_>,
+) -> SimpleLoginResult<T> {
+    let response = send(client, method.clone(), endpoint, payload).await?;
+
+    deserialize(&response, method, &client.get_url(endpoint))
+}
+
+/// Send a request to a public endpoint of the api (without token) and deserialize the response
+pub(crate) async fn request_public<S: SimpleLogin, T: DeserializeOwned>(
+    client: &S,
+    endpoint: &str,
+    payload: &Payload<'_>,
+) -> SimpleLoginResult<T> {
+    let url = client.get_url(endpoint);
+    let response = client.get_http().post_public(&url, payload).await?;
+
+    deserialize(&response, Method::POST, &url)
+}
+
+/// Deserialize a successful response, keeping the path of the field in error
+pub(crate) fn deserialize<T: DeserializeOwned>(
+    body: &str,
+    method: Method,
+    url: &str,
+) -> SimpleLoginResult<T> {
+    let deserializer = &mut serde_json::Deserializer::from_str(body);
+
+    serde_path_to_error::deserialize(deserializer).map_err(|source| {
+        SimpleLoginError::DeserializeApiResponse {
+            source,
+            context: Box::new(ErrorContext::new(method, url).with_body(body)),
+        }
+    })
+}
+
+/// Turn the status and the body of a response into the errors of the crate, `Ok` on success.
+///
+/// Used by [`crate::HttpClient`], and by custom [`BaseHttpClient`] transports to return
+/// the same typed errors
+pub fn parse_error_from_response<S>(
     body: S,
     status: StatusCode,
-    path: D,
+    headers: &HeaderMap,
+    method: &Method,
+    url: &str,
 ) -> SimpleLoginResult
 where
     S: AsRef<str>,
-    D: AsRef<str> + std::fmt::Display,
 {
+    let context = || {
+        Box::new(
+            ErrorContext::new(method.clone(), url)
+                .with_status(status)
+                .with_body(body.as_ref()),
+        )
+    };
+
+    // Message sent by the api, if any
+    let error = || {
+        serde_json::from_str::<ErrorData>(body.as_ref())
+            .ok()
+            .map(|error| error.error)
+    };
+
     match status.as_u16() {
         200 | 201 => Ok(()),
         400 | 403 => {
-            let error = serde_json::from_str::<ErrorData>(body.as_ref())
-                .map_err(|e| SimpleLoginError::DeserializeApiErrorResponse(e))?;
+            let error = serde_json::from_str::<ErrorData>(body.as_ref()).map_err(|source| {
+                SimpleLoginError::DeserializeApiErrorResponse {
+                    source,
+                    context: context(),
+                }
+            })?;
 
-            Err(SimpleLoginError::ApiErrorResponse { error: error.error })
+            Err(SimpleLoginError::ApiErrorResponse {
+                error: error.error,
+                context: context(),
+            })
         }
-        401 => Err(SimpleLoginError::BadCredentials),
-        410 => Err(SimpleLoginError::TooManyWrongTries),
-        440 => Err(SimpleLoginError::NeedSudo),
+        401 => Err(SimpleLoginError::BadCredentials { context: context() }),
+        410 => Err(SimpleLoginError::TooManyWrongTries { context: context() }),
+        404 => Err(SimpleLoginError::NotFound {
+            error: error(),
+            context: context(),
+        }),
+        409 => Err(SimpleLoginError::Conflict {
+            error: error(),
+            context: context(),
+        }),
+        422 => Err(SimpleLoginError::UnprocessableEntity {
+            error: error(),
+            context: context(),
+        }),
+        429 => Err(SimpleLoginError::RateLimited {
+            retry_after: parse_retry_after(headers),
+            context: context(),
+        }),
+        440 => Err(SimpleLoginError::NeedSudo { context: context() }),
+        500..=599 => Err(SimpleLoginError::ServerError {
+            status,
+            error: error(),
+            context: context(),
+        }),
         _ => Err(SimpleLoginError::RequestStatusCode {
-            path: path.to_string(),
             status,
+            context: context(),
         }),
     }
 }
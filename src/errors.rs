@@ -1,36 +1,243 @@
+use std::fmt;
+use std::time::Duration;
+
+use reqwest::{Method, StatusCode};
 use thiserror::Error;
 
+use crate::{redact, ApiErrorKind};
+
+/// Maximum length of the response body kept in an error
+const MAX_BODY_LEN: usize = 512;
+
+/// Request that led to an error
+#[derive(Debug, Clone)]
+pub struct ErrorContext {
+    pub method: Method,
+    /// Path of the endpoint, e.g. `/api/v2/aliases`
+    pub endpoint: String,
+    /// Status of the response, `None` when no response was received or when the response
+    /// was successful but could not be deserialized
+    pub status: Option<StatusCode>,
+    /// Body of the response, truncated and with the secrets redacted
+    pub body: Option<String>,
+}
+
+impl ErrorContext {
+    pub(crate) fn new(method: Method, url: &str) -> Self {
+        Self {
+            method,
+            endpoint: endpoint_path(url),
+            status: None,
+            body: None,
+        }
+    }
+
+    pub(crate) fn with_status(mut self, status: StatusCode) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    pub(crate) fn with_body(mut self, body: &str) -> Self {
+        self.body = Some(redact::scrub_body(body, MAX_BODY_LEN));
+        self
+    }
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.method, self.endpoint)?;
+        if let Some(status) = self.status {
+            write!(f, " ({status})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Path of an url, or the url itself if it can't be parsed
+pub(crate) fn endpoint_path(url: &str) -> String {
+    reqwest::Url::parse(url).map_or_else(|_| url.to_owned(), |url| url.path().to_owned())
+}
+
 #[derive(Error, Debug)]
 pub enum SimpleLoginError {
-    #[error("Unable to request '{1}'")]
-    Request(#[source] reqwest::Error, String),
+    #[error("Unable to request '{context}'")]
+    Request {
+        #[source]
+        source: reqwest::Error,
+        context: Box<ErrorContext>,
+    },
 
-    #[error("Unable to request '{}' with status {}", .path, .status)]
+    #[error("Unable to request '{context}'")]
     RequestStatusCode {
-        path: String,
-        status: reqwest::StatusCode,
+        status: StatusCode,
+        context: Box<ErrorContext>,
+    },
+
+    #[error("{error} ('{context}')")]
+    ApiErrorResponse {
+        error: String,
+        context: Box<ErrorContext>,
+    },
+
+    #[error("Bad credentials ('{context}')")]
+    BadCredentials { context: Box<ErrorContext> },
+
+    #[error("Not found{} ('{context}')", message(.error))]
+    NotFound {
+        error: Option<String>,
+        context: Box<ErrorContext>,
+    },
+
+    #[error("Conflict{} ('{context}')", message(.error))]
+    Conflict {
+        error: Option<String>,
+        context: Box<ErrorContext>,
+    },
+
+    #[error("Unprocessable request{} ('{context}')", message(.error))]
+    UnprocessableEntity {
+        error: Option<String>,
+        context: Box<ErrorContext>,
+    },
+
+    #[error("Rate limited{} ('{context}')", retry_after_message(.retry_after))]
+    RateLimited {
+        retry_after: Option<Duration>,
+        context: Box<ErrorContext>,
     },
 
-    #[error("{}", .error)]
-    ApiErrorResponse { error: String },
+    #[error("Server error{} ('{context}')", message(.error))]
+    ServerError {
+        status: StatusCode,
+        error: Option<String>,
+        context: Box<ErrorContext>,
+    },
+
+    #[error("Invalid base url '{0}'")]
+    InvalidBaseUrl(String),
+
+    #[error("Unable to retire the mailbox: {0}")]
+    RetireMailbox(String),
 
-    #[error("Bad credentials")]
-    BadCredentials,
+    #[error("Invalid next path '{0}', it must be a path relative to the dashboard (e.g. '/dashboard/setting')")]
+    InvalidNextPath(String),
+
+    #[error("Unable to build the http client")]
+    BuildHttpClient(#[source] reqwest::Error),
+
+    #[error("Unsupported http method '{0}'")]
+    UnsupportedMethod(reqwest::Method),
 
     #[error("Token not set")]
     TokenNotSet,
 
-    #[error("Sudo is needed to access this endpoint")]
-    NeedSudo,
+    #[error("Unable to get the token from the provider")]
+    TokenProvider(#[source] std::io::Error),
+
+    #[error("Sudo is needed to access this endpoint ('{context}')")]
+    NeedSudo { context: Box<ErrorContext> },
+
+    #[error(
+        "Too many wrong tries, please ask for a reactivation 'api/auth/reactivate' ('{context}')"
+    )]
+    TooManyWrongTries { context: Box<ErrorContext> },
+
+    #[error("Unable to deserialize the data from an error return by the api ('{context}')")]
+    DeserializeApiErrorResponse {
+        #[source]
+        source: serde_json::Error,
+        context: Box<ErrorContext>,
+    },
+
+    #[error("Unable to deserialize the csv at row {row}: {error} ('{context}')")]
+    DeserializeCsvResponse {
+        row: usize,
+        error: String,
+        context: Box<ErrorContext>,
+    },
+
+    #[error("Unable to deserialize the data at '{}' ('{context}')", .source.path())]
+    DeserializeApiResponse {
+        #[source]
+        source: serde_path_to_error::Error<serde_json::Error>,
+        context: Box<ErrorContext>,
+    },
+}
 
-    #[error("Too many wrong tries, please ask for a reactivation 'api/auth/reactivate'")]
-    TooManyWrongTries,
+impl SimpleLoginError {
+    /// Request that led to the error, if any
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            Self::Request { context, .. }
+            | Self::RequestStatusCode { context, .. }
+            | Self::ApiErrorResponse { context, .. }
+            | Self::BadCredentials { context }
+            | Self::NotFound { context, .. }
+            | Self::Conflict { context, .. }
+            | Self::UnprocessableEntity { context, .. }
+            | Self::RateLimited { context, .. }
+            | Self::ServerError { context, .. }
+            | Self::NeedSudo { context }
+            | Self::TooManyWrongTries { context }
+            | Self::DeserializeApiErrorResponse { context, .. }
+            | Self::DeserializeCsvResponse { context, .. }
+            | Self::DeserializeApiResponse { context, .. } => Some(context),
+            Self::InvalidBaseUrl(_)
+            | Self::InvalidNextPath(_)
+            | Self::RetireMailbox(_)
+            | Self::BuildHttpClient(_)
+            | Self::UnsupportedMethod(_)
+            | Self::TokenNotSet
+            | Self::TokenProvider(_) => None,
+        }
+    }
 
-    #[error("Unable to deserialize the data from an error return by the api")]
-    DeserializeApiErrorResponse(#[source] serde_json::Error),
+    /// Kind of the error returned by the api, parsed from its message
+    pub fn api_error_kind(&self) -> Option<ApiErrorKind> {
+        match self {
+            Self::ApiErrorResponse { error, .. } => Some(ApiErrorKind::from_message(error)),
+            Self::NotFound { error, .. }
+            | Self::Conflict { error, .. }
+            | Self::UnprocessableEntity { error, .. }
+            | Self::ServerError { error, .. } => error.as_deref().map(ApiErrorKind::from_message),
+            _ => None,
+        }
+    }
+
+    /// Whether the request may succeed if sent again later
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Request { source, .. } => source.is_connect() || source.is_timeout(),
+            Self::RateLimited { .. } => true,
+            Self::ServerError { status, .. } => matches!(status.as_u16(), 500 | 502 | 503 | 504),
+            _ => false,
+        }
+    }
+
+    /// Whether the error comes from the authentication (token, credentials or sudo)
+    pub fn is_auth_error(&self) -> bool {
+        matches!(
+            self,
+            Self::BadCredentials { .. }
+                | Self::NeedSudo { .. }
+                | Self::TooManyWrongTries { .. }
+                | Self::TokenNotSet
+                | Self::TokenProvider(_)
+        )
+    }
+}
+
+fn retry_after_message(retry_after: &Option<Duration>) -> String {
+    retry_after
+        .map(|retry_after| format!(", retry after {}s", retry_after.as_secs()))
+        .unwrap_or_default()
+}
 
-    #[error("Unable to deserialize the data")]
-    DeserializeApiResponse(#[source] serde_json::Error),
+fn message(error: &Option<String>) -> String {
+    error
+        .as_ref()
+        .map(|error| format!(": {error}"))
+        .unwrap_or_default()
 }
 
 pub type SimpleLoginResult<T = ()> = Result<T, SimpleLoginError>;
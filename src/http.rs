@@ -10,8 +10,13 @@ pub type Headers = HashMap<String, String>;
 pub type Query<'a> = HashMap<&'a str, String>;
 pub type Payload<'a> = (Option<&'a Query<'a>>, Option<&'a Value>);
 
+/// Transport sending the requests of the client.
+///
+/// The retries, the rate limiter and the middlewares are features of [`crate::HttpClient`],
+/// a custom transport has to implement them itself and can turn the responses into the
+/// errors of the crate with [`crate::parse_error_from_response`].
 #[async_trait]
-pub trait BaseHttpClient: Send + Default + Clone + fmt::Debug {
+pub trait BaseHttpClient: Send + Sync + Default + Clone + fmt::Debug {
     async fn get(
         &self,
         token: Option<&str>,
@@ -19,6 +24,16 @@ pub trait BaseHttpClient: Send + Default + Clone + fmt::Debug {
         payload: &Payload,
     ) -> SimpleLoginResult<String>;
 
+    /// GET request returning the body as received, without decoding it as text
+    async fn get_bytes(
+        &self,
+        token: Option<&str>,
+        url: &str,
+        payload: &Payload,
+    ) -> SimpleLoginResult<Vec<u8>> {
+        self.get(token, url, payload).await.map(String::into_bytes)
+    }
+
     async fn post(
         &self,
         token: Option<&str>,
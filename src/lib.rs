@@ -1,9 +1,40 @@
+mod api_error;
+mod auth;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+mod builder;
 mod endpoints;
 mod errors;
 mod http;
+mod middleware;
 mod models;
+mod rate_limit;
+mod redact;
+mod retry;
+mod secret;
+mod sudo;
+mod token;
+#[cfg(feature = "totp")]
+mod totp;
+#[cfg(feature = "vault")]
+mod vault;
 
+pub use api_error::*;
+pub use auth::*;
+pub use builder::*;
 pub use endpoints::*;
 pub use errors::*;
 pub use http::*;
+pub use middleware::*;
 pub use models::*;
+pub use rate_limit::*;
+pub use retry::*;
+pub use secret::*;
+pub use sudo::*;
+pub use token::*;
+#[cfg(feature = "totp")]
+pub use totp::*;
+#[cfg(feature = "vault")]
+pub use vault::*;
+
+pub use reqwest::{header::HeaderMap, Certificate, Method, Proxy, StatusCode};
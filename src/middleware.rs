@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use reqwest::{header::HeaderMap, Method, StatusCode};
+use serde_json::Value;
+
+use crate::{redact, Headers};
+
+/// Hook called by the http client around each request.
+///
+/// Middlewares are called in the order they were added, `on_request` once per call
+/// (retries replay the modified request) and `on_response` on each response received,
+/// including the rate limited and server errors that are retried, before the api errors
+/// are parsed.
+///
+/// Middlewares are run by [`crate::HttpClient`], not by a custom [`crate::BaseHttpClient`].
+pub trait Middleware: Send + Sync {
+    /// Inspect or modify the outgoing request
+    fn on_request(&self, _request: &mut RequestParts) {}
+
+    /// Observe the response
+    fn on_response(&self, _request: &RequestParts, _response: &ResponseParts) {}
+}
+
+impl<M: Middleware + ?Sized> Middleware for Arc<M> {
+    fn on_request(&self, request: &mut RequestParts) {
+        (**self).on_request(request);
+    }
+
+    fn on_response(&self, request: &RequestParts, response: &ResponseParts) {
+        (**self).on_response(request, response);
+    }
+}
+
+/// Outgoing request, as seen by the middlewares.
+///
+/// `Debug` redacts the `Authentication` header and the secrets of the body
+#[derive(Clone)]
+pub struct RequestParts {
+    pub method: Method,
+    pub url: String,
+    /// Headers of the request, including the `Authentication` header when a token is set
+    pub headers: Headers,
+    pub query: HashMap<String, String>,
+    pub json: Option<Value>,
+}
+
+impl fmt::Debug for RequestParts {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let headers: HashMap<_, _> = self
+            .headers
+            .iter()
+            .map(
+                |(name, value)| match name.eq_ignore_ascii_case("Authentication") {
+                    true => (name, "[REDACTED]"),
+                    false => (name, value.as_str()),
+                },
+            )
+            .collect();
+
+        f.debug_struct("RequestParts")
+            .field("method", &self.method)
+            .field("url", &self.url)
+            .field("headers", &headers)
+            .field("query", &self.query)
+            .field("json", &self.json.as_ref().map(redact::redact_json))
+            .finish()
+    }
+}
+
+/// Response received, as seen by the middlewares
+#[derive(Debug, Clone)]
+pub struct ResponseParts<'a> {
+    pub status: StatusCode,
+    pub headers: Headers,
+    pub body: &'a str,
+}
+
+impl<'a> ResponseParts<'a> {
+    pub(crate) fn new(status: StatusCode, headers: &HeaderMap, body: &'a str) -> Self {
+        Self {
+            status,
+            headers: headers
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some((name.to_string(), value.to_str().ok()?.to_owned()))
+                })
+                .collect(),
+            body,
+        }
+    }
+}
+
+#[derive(Default, Clone)]
+pub(crate) struct Middlewares(Vec<Arc<dyn Middleware>>);
+
+impl Middlewares {
+    pub(crate) fn push(&mut self, middleware: Arc<dyn Middleware>) {
+        self.0.push(middleware);
+    }
+
+    pub(crate) fn on_request(&self, request: &mut RequestParts) {
+        for middleware in &self.0 {
+            middleware.on_request(request);
+        }
+    }
+
+    pub(crate) fn on_response(&self, request: &RequestParts, response: &ResponseParts) {
+        for middleware in &self.0 {
+            middleware.on_response(request, response);
+        }
+    }
+}
+
+impl fmt::Debug for Middlewares {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Middlewares({})", self.0.len())
+    }
+}
@@ -1,17 +1,19 @@
 use serde::Deserialize;
 
+use crate::Secret;
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct LoginData {
-    pub api_key: Option<String>,
+    pub api_key: Option<Secret>,
     pub email: String,
     pub mfa_enabled: bool,
-    pub mfa_key: Option<String>,
+    pub mfa_key: Option<Secret>,
     pub name: String,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct MfaData {
-    pub api_key: String,
+    pub api_key: Secret,
     pub email: String,
     pub name: String,
 }
@@ -27,12 +29,36 @@ pub struct UserInfoData {
     pub name: String,
 }
 
+#[derive(Debug, Deserialize, Clone)]
+pub struct StatsData {
+    pub nb_alias: usize,
+    pub nb_block: usize,
+    pub nb_forward: usize,
+    pub nb_reply: usize,
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct CookieTokenData {
-    pub token: String,
+    pub token: Secret,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct ApiKeyData {
-    pub api_key: String,
+    pub api_key: Secret,
+}
+
+/// Result of an api key rotation
+#[derive(Debug, Clone)]
+pub struct ApiKeyRotationData {
+    /// New api key, now used by the client through a [`crate::StaticToken`] which replaced
+    /// its token provider. It must be saved wherever the previous provider read the old key
+    pub api_key: Secret,
+    /// Redacted old key
+    pub old_key: String,
+    /// Redacted new key
+    pub new_key: String,
+    /// User returned by the verification of the new key
+    pub user: UserInfoData,
+    /// Whether the old key was logged out, it is still valid otherwise
+    pub old_key_logged_out: bool,
 }
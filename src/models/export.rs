@@ -0,0 +1,9 @@
+/// Row of the aliases csv export
+#[derive(Debug, Clone)]
+pub struct AliasExportData {
+    pub email: String,
+    pub note: Option<String>,
+    pub enabled: bool,
+    /// Emails of the mailboxes
+    pub mailboxes: Vec<String>,
+}
@@ -9,3 +9,20 @@ pub struct MailboxData {
     pub nb_alias: usize,
     pub creation_timestamp: usize,
 }
+
+/// Result of the retirement of a mailbox
+#[derive(Debug, Clone)]
+pub struct RetiredMailboxData {
+    /// The deleted mailbox
+    pub mailbox: MailboxData,
+    /// The mailbox receiving the aliases
+    pub target: MailboxData,
+    /// Number of aliases of the mailbox before its deletion, read from the mailbox list.
+    /// The api does not return the number of aliases it actually moved to the target
+    pub transferred_aliases: usize,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub(crate) struct VecMailboxData {
+    pub mailboxes: Vec<MailboxData>,
+}
@@ -4,6 +4,7 @@ pub mod account;
 pub mod alias;
 pub mod contact;
 pub mod custom_domain;
+pub mod export;
 pub mod mailbox;
 pub mod notification;
 pub mod setting;
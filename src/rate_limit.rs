@@ -0,0 +1,128 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Token bucket limiting the number of requests sent per second.
+///
+/// Clones share the same bucket, so every client using a clone of the limiter
+/// is throttled to the same budget.
+///
+/// The limiter is applied by [`crate::HttpClient`], a custom [`crate::BaseHttpClient`]
+/// has to call [`RateLimiter::acquire`] itself.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    requests_per_second: f64,
+    burst: f64,
+    bucket: Mutex<Bucket>,
+    requests: AtomicU64,
+    throttled_requests: AtomicU64,
+    total_wait_us: AtomicU64,
+    max_wait_us: AtomicU64,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Snapshot of the time spent waiting on the rate limiter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimiterMetrics {
+    /// Number of requests that went through the limiter
+    pub requests: u64,
+    /// Number of requests that had to wait
+    pub throttled_requests: u64,
+    /// Total time spent waiting
+    pub total_wait: Duration,
+    /// Longest wait of a single request
+    pub max_wait: Duration,
+}
+
+impl RateLimiter {
+    /// Allow `requests_per_second` requests per second, with bursts of the same size
+    pub fn new(requests_per_second: f64) -> Self {
+        Self::with_burst(
+            requests_per_second,
+            requests_per_second.ceil().max(1.0) as u32,
+        )
+    }
+
+    /// Allow `requests_per_second` requests per second, with bursts of up to `burst` requests
+    pub fn with_burst(requests_per_second: f64, burst: u32) -> Self {
+        assert!(
+            requests_per_second > 0.0,
+            "requests_per_second must be positive"
+        );
+        let burst = f64::from(burst.max(1));
+
+        Self {
+            inner: Arc::new(Inner {
+                requests_per_second,
+                burst,
+                bucket: Mutex::new(Bucket {
+                    tokens: burst,
+                    last_refill: Instant::now(),
+                }),
+                requests: AtomicU64::new(0),
+                throttled_requests: AtomicU64::new(0),
+                total_wait_us: AtomicU64::new(0),
+                max_wait_us: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    pub fn requests_per_second(&self) -> f64 {
+        self.inner.requests_per_second
+    }
+
+    pub fn metrics(&self) -> RateLimiterMetrics {
+        RateLimiterMetrics {
+            requests: self.inner.requests.load(Ordering::Relaxed),
+            throttled_requests: self.inner.throttled_requests.load(Ordering::Relaxed),
+            total_wait: Duration::from_micros(self.inner.total_wait_us.load(Ordering::Relaxed)),
+            max_wait: Duration::from_micros(self.inner.max_wait_us.load(Ordering::Relaxed)),
+        }
+    }
+
+    /// Wait until a request can be sent
+    pub async fn acquire(&self) {
+        let wait = self.reserve();
+
+        let inner = &self.inner;
+        inner.requests.fetch_add(1, Ordering::Relaxed);
+
+        if !wait.is_zero() {
+            let wait_us = wait.as_micros() as u64;
+            inner.throttled_requests.fetch_add(1, Ordering::Relaxed);
+            inner.total_wait_us.fetch_add(wait_us, Ordering::Relaxed);
+            inner.max_wait_us.fetch_max(wait_us, Ordering::Relaxed);
+
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Take a token from the bucket and return how long to wait before it is available.
+    /// The bucket may go negative so that waiting requests are served in order.
+    fn reserve(&self) -> Duration {
+        let inner = &self.inner;
+        let mut bucket = inner.bucket.lock().unwrap_or_else(|e| e.into_inner());
+
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * inner.requests_per_second).min(inner.burst);
+        bucket.last_refill = now;
+        bucket.tokens -= 1.0;
+
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / inner.requests_per_second)
+        }
+    }
+}
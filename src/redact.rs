@@ -0,0 +1,131 @@
+use serde_json::Value;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Keys of the payloads and responses holding secrets
+const SECRET_KEYS: &[&str] = &[
+    "password",
+    "api_key",
+    "mfa_key",
+    "mfa_token",
+    "token",
+    "code",
+];
+
+/// Copy of a json value with the secrets replaced
+pub(crate) fn redact_json(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| {
+                    let value = if SECRET_KEYS.contains(&key.as_str()) && !value.is_null() {
+                        Value::String(REDACTED.to_owned())
+                    } else {
+                        redact_json(value)
+                    };
+                    (key.clone(), value)
+                })
+                .collect(),
+        ),
+        Value::Array(values) => Value::Array(values.iter().map(redact_json).collect()),
+        value => value.clone(),
+    }
+}
+
+/// Redact a key, keeping its last characters (at most 4, and at most half of it) to identify it
+pub(crate) fn redact_key(key: &str) -> String {
+    let shown = (key.len() / 2).min(4);
+    match key.get(key.len() - shown..) {
+        Some(end) if shown > 0 => format!("****{end}"),
+        _ => REDACTED.to_owned(),
+    }
+}
+
+/// Redact a response body and truncate it to `max_len` characters.
+/// Bodies that are not json (html pages, csv exports) can't be scrubbed and are not kept
+pub(crate) fn scrub_body(body: &str, max_len: usize) -> String {
+    let body = match serde_json::from_str::<Value>(body) {
+        Ok(value) => redact_json(&value).to_string(),
+        Err(_) => format!("<{} bytes, not json>", body.len()),
+    };
+
+    match body.char_indices().nth(max_len) {
+        Some((index, _)) => format!("{}...", &body[..index]),
+        None => body,
+    }
+}
+
+/// Redact a response body. Bodies that are not json are not kept as they can't be scrubbed
+#[cfg(feature = "tracing")]
+pub(crate) fn redact_body(body: &str) -> String {
+    match serde_json::from_str::<Value>(body) {
+        Ok(value) => redact_json(&value).to_string(),
+        Err(_) => format!("<{} bytes>", body.len()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn redact_nested_json() {
+        let value = json!({
+            "email": "user@example.com",
+            "password": "password",
+            "mfa_key": null,
+            "user": {"api_key": "secret", "name": "name"},
+            "aliases": [{"token": "secret"}, {"code": 123456}, "plain"],
+        });
+
+        assert_eq!(
+            redact_json(&value),
+            json!({
+                "email": "user@example.com",
+                "password": "[REDACTED]",
+                "mfa_key": null,
+                "user": {"api_key": "[REDACTED]", "name": "name"},
+                "aliases": [{"token": "[REDACTED]"}, {"code": "[REDACTED]"}, "plain"],
+            })
+        );
+    }
+
+    #[test]
+    fn redact_keys() {
+        assert_eq!(redact_key("abcdefghijklmnop"), "****mnop");
+        assert_eq!(redact_key("abcdef"), "****def");
+        assert_eq!(redact_key("ab"), "****b");
+        assert_eq!(redact_key("a"), REDACTED);
+        assert_eq!(redact_key(""), REDACTED);
+        // Not a char boundary
+        assert_eq!(redact_key("aé"), REDACTED);
+    }
+
+    #[test]
+    fn scrub_json_body() {
+        assert_eq!(
+            scrub_body(r#"{"api_key": "secret", "name": "name"}"#, 100),
+            r#"{"api_key":"[REDACTED]","name":"name"}"#
+        );
+    }
+
+    #[test]
+    fn scrub_body_not_json() {
+        assert_eq!(
+            scrub_body("alias,note\na@simplelogin.co,secret note\n", 100),
+            "<40 bytes, not json>"
+        );
+        assert_eq!(
+            scrub_body("<html>error</html>", 100),
+            "<18 bytes, not json>"
+        );
+    }
+
+    #[test]
+    fn scrub_body_truncated_on_char_boundary() {
+        assert_eq!(scrub_body(r#""éééé""#, 3), r#""éé..."#);
+        assert_eq!(scrub_body(r#""éé""#, 4), r#""éé""#);
+    }
+}
@@ -0,0 +1,236 @@
+use std::time::{Duration, SystemTime};
+
+use rand::Rng;
+use reqwest::{header::HeaderMap, Method, StatusCode};
+
+/// Retry policy applied by the http client on rate limits and transient failures.
+///
+/// Only [`crate::HttpClient`] retries, a custom [`crate::BaseHttpClient`] is called once per request
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first one (`1` disables retries)
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled on each following retry
+    pub initial_backoff: Duration,
+    /// Upper bound of the computed backoff
+    pub max_backoff: Duration,
+    /// Randomize the backoff to avoid synchronized retries
+    pub jitter: bool,
+    /// Wait for the delay sent by the api in the `Retry-After` header when present.
+    /// A delay above `max_backoff` is not waited and the error is returned instead
+    pub respect_retry_after: bool,
+    /// Also retry non idempotent requests (POST, PATCH) on server errors and network failures.
+    /// Rate limited requests (429) are always retried as they were not processed by the api
+    pub retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            jitter: true,
+            respect_retry_after: true,
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Never retry a request
+    pub fn disabled() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn should_retry_status(
+        &self,
+        method: &Method,
+        url: &str,
+        status: StatusCode,
+    ) -> bool {
+        match status.as_u16() {
+            429 => true,
+            500 | 502 | 503 | 504 => self.retry_non_idempotent || is_idempotent(method, url),
+            _ => false,
+        }
+    }
+
+    pub(crate) fn should_retry_error(
+        &self,
+        method: &Method,
+        url: &str,
+        error: &reqwest::Error,
+    ) -> bool {
+        // A connection error means the request never reached the api
+        error.is_connect()
+            || ((error.is_timeout() || error.is_request())
+                && (self.retry_non_idempotent || is_idempotent(method, url)))
+    }
+
+    /// Delay to wait before the given retry (starting at 1)
+    pub(crate) fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff);
+
+        if self.jitter {
+            // Equal jitter: keep at least half of the backoff
+            let half = backoff / 2;
+            half + rand::thread_rng().gen_range(Duration::ZERO..=half)
+        } else {
+            backoff
+        }
+    }
+
+    /// Delay to wait before the given retry, honoring the `Retry-After` header if allowed.
+    /// Returns `None` when the api asks to wait longer than `max_backoff`
+    pub(crate) fn delay(&self, retry: u32, headers: &HeaderMap) -> Option<Duration> {
+        if self.respect_retry_after {
+            if let Some(retry_after) = parse_retry_after(headers) {
+                return (retry_after <= self.max_backoff).then_some(retry_after);
+            }
+        }
+        Some(self.backoff(retry))
+    }
+}
+
+/// POST endpoints that only read data (the alias search), retried like a GET
+const READ_ONLY_POSTS: &[&str] = &["/api/v2/aliases"];
+
+fn is_idempotent(method: &Method, url: &str) -> bool {
+    match *method {
+        Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS => true,
+        Method::POST => READ_ONLY_POSTS.iter().any(|path| url.ends_with(path)),
+        _ => false,
+    }
+}
+
+/// Parse the `Retry-After` header, either a number of seconds or an http date
+pub(crate) fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(
+        date.duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::{HeaderValue, RETRY_AFTER};
+
+    use super::*;
+
+    fn retry_after(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn retry_after_seconds() {
+        let policy = RetryPolicy::default();
+
+        assert_eq!(
+            parse_retry_after(&retry_after("12")),
+            Some(Duration::from_secs(12))
+        );
+        assert_eq!(
+            policy.delay(1, &retry_after("12")),
+            Some(Duration::from_secs(12))
+        );
+        assert_eq!(parse_retry_after(&retry_after("soon")), None);
+    }
+
+    #[test]
+    fn retry_after_http_date() {
+        let date = httpdate::fmt_http_date(SystemTime::now() + Duration::from_secs(20));
+        let delay = parse_retry_after(&retry_after(&date)).unwrap();
+        assert!(delay > Duration::from_secs(18) && delay <= Duration::from_secs(20));
+
+        let past = httpdate::fmt_http_date(SystemTime::now() - Duration::from_secs(20));
+        assert_eq!(parse_retry_after(&retry_after(&past)), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn retry_after_above_max_backoff() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.delay(1, &retry_after("3600")), None);
+
+        let policy = RetryPolicy {
+            respect_retry_after: false,
+            jitter: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            policy.delay(1, &retry_after("3600")),
+            Some(policy.initial_backoff)
+        );
+    }
+
+    #[test]
+    fn backoff_capped() {
+        let policy = RetryPolicy {
+            jitter: false,
+            ..Default::default()
+        };
+
+        assert_eq!(policy.backoff(1), Duration::from_millis(500));
+        assert_eq!(policy.backoff(2), Duration::from_secs(1));
+        assert_eq!(policy.backoff(3), Duration::from_secs(2));
+        assert_eq!(policy.backoff(10), policy.max_backoff);
+        assert_eq!(policy.backoff(u32::MAX), policy.max_backoff);
+    }
+
+    #[test]
+    fn backoff_jitter_bounds() {
+        let policy = RetryPolicy::default();
+
+        for retry in 1..10 {
+            let backoff = RetryPolicy {
+                jitter: false,
+                ..policy.clone()
+            }
+            .backoff(retry);
+            let delay = policy.backoff(retry);
+            assert!(delay >= backoff / 2 && delay <= backoff, "{delay:?}");
+        }
+    }
+
+    #[test]
+    fn alias_search_retried() {
+        let policy = RetryPolicy::default();
+        let status = StatusCode::BAD_GATEWAY;
+
+        assert!(policy.should_retry_status(
+            &Method::POST,
+            "https://app.simplelogin.io/api/v2/aliases",
+            status
+        ));
+        assert!(!policy.should_retry_status(
+            &Method::POST,
+            "https://app.simplelogin.io/api/v3/alias/custom/new",
+            status
+        ));
+        assert!(policy.should_retry_status(
+            &Method::GET,
+            "https://app.simplelogin.io/api/v2/aliases",
+            status
+        ));
+    }
+}
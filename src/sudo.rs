@@ -0,0 +1,121 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+
+use crate::Secret;
+
+/// Provides the password of the account when the api asks for the sudo mode
+#[async_trait]
+pub trait PasswordProvider: Send + Sync {
+    /// Password of the account, `None` to not enable the sudo mode
+    async fn password(&self) -> Option<Secret>;
+}
+
+#[async_trait]
+impl<F> PasswordProvider for F
+where
+    F: Fn() -> Option<Secret> + Send + Sync,
+{
+    async fn password(&self) -> Option<Secret> {
+        self()
+    }
+}
+
+/// Enable the sudo mode when an endpoint answers `NeedSudo` and replay the request once.
+///
+/// Clones share the same sudo window, so the concurrent requests failing together only
+/// enable the sudo mode once.
+#[derive(Clone)]
+pub struct SudoPolicy {
+    provider: Arc<dyn PasswordProvider>,
+    window: Duration,
+    state: Arc<Mutex<Option<SudoState>>>,
+}
+
+/// Last time the sudo mode was enabled, and for which token
+struct SudoState {
+    enabled_at: Instant,
+    token: Option<Arc<str>>,
+}
+
+impl std::fmt::Debug for SudoPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("SudoPolicy")
+            .field("window", &self.window)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SudoPolicy {
+    /// The sudo mode of the api lasts 5 minutes
+    pub const DEFAULT_WINDOW: Duration = Duration::from_secs(5 * 60);
+
+    pub fn new<P: PasswordProvider + 'static>(provider: P) -> Self {
+        Self {
+            provider: Arc::new(provider),
+            window: Self::DEFAULT_WINDOW,
+            state: Default::default(),
+        }
+    }
+
+    /// Duration during which the sudo mode is considered enabled
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Whether the sudo mode was enabled for `token` during the window
+    pub fn is_active(&self, token: Option<&str>) -> bool {
+        match self.state.try_lock() {
+            Ok(state) => state
+                .as_ref()
+                .is_some_and(|state| self.is_valid(state, token)),
+            Err(_) => false,
+        }
+    }
+
+    /// Enable the sudo mode with `enable`, unless it was already enabled for `token`
+    /// after `since` (by a concurrent request). Returns `None` when the provider
+    /// has no password
+    pub(crate) async fn escalate<F, Fut, E>(
+        &self,
+        token: Option<&str>,
+        since: Instant,
+        enable: F,
+    ) -> Option<Result<(), E>>
+    where
+        F: FnOnce(Secret) -> Fut,
+        Fut: std::future::Future<Output = Result<(), E>>,
+    {
+        let mut state = self.state.lock().await;
+
+        if let Some(current) = state.as_ref() {
+            if current.enabled_at > since && self.is_valid(current, token) {
+                return Some(Ok(()));
+            }
+        }
+
+        let password = self.provider.password().await?;
+
+        if let Err(error) = enable(password).await {
+            return Some(Err(error));
+        }
+
+        *state = Some(SudoState {
+            enabled_at: Instant::now(),
+            token: token.map(Into::into),
+        });
+
+        Some(Ok(()))
+    }
+
+    fn is_valid(&self, state: &SudoState, token: Option<&str>) -> bool {
+        state.token.as_deref() == token && state.enabled_at.elapsed() < self.window
+    }
+}
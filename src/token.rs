@@ -0,0 +1,219 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use async_trait::async_trait;
+
+use crate::{Secret, SimpleLoginError, SimpleLoginResult};
+
+/// Provides the token authenticating the requests, consulted before each request
+#[async_trait]
+pub trait TokenProvider: Send + Sync + fmt::Debug {
+    /// Current token, `None` to send the request without token
+    async fn token(&self) -> SimpleLoginResult<Option<Arc<str>>>;
+}
+
+/// Fixed token, wiped from memory on drop
+#[derive(Clone)]
+pub struct StaticToken(Secret);
+
+impl StaticToken {
+    pub fn new<S: Into<Secret>>(token: S) -> Self {
+        Self(token.into())
+    }
+}
+
+impl fmt::Debug for StaticToken {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("StaticToken").field(&"[REDACTED]").finish()
+    }
+}
+
+#[async_trait]
+impl TokenProvider for StaticToken {
+    async fn token(&self) -> SimpleLoginResult<Option<Arc<str>>> {
+        Ok(Some(self.0.expose().into()))
+    }
+}
+
+/// Token read from an environment variable on each request
+#[derive(Debug, Clone)]
+pub struct EnvToken {
+    name: String,
+}
+
+impl EnvToken {
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Self { name: name.into() }
+    }
+}
+
+#[async_trait]
+impl TokenProvider for EnvToken {
+    async fn token(&self) -> SimpleLoginResult<Option<Arc<str>>> {
+        let token = std::env::var(&self.name).map_err(|_| {
+            SimpleLoginError::TokenProvider(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("environment variable '{}' not set", self.name),
+            ))
+        })?;
+
+        parse_token(&token).map(Some)
+    }
+}
+
+/// Token read from a file, read again when the file is modified
+pub struct FileToken {
+    path: PathBuf,
+    cache: Mutex<Option<(FileVersion, Arc<str>)>>,
+}
+
+/// Modification time and length of the file, the length catches the writes made
+/// within the same tick of the modification time
+#[derive(PartialEq, Eq)]
+struct FileVersion {
+    modified: SystemTime,
+    len: u64,
+}
+
+impl FileToken {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self {
+            path: path.into(),
+            cache: Mutex::new(None),
+        }
+    }
+
+    fn cache(&self) -> std::sync::MutexGuard<'_, Option<(FileVersion, Arc<str>)>> {
+        self.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl fmt::Debug for FileToken {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FileToken")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl TokenProvider for FileToken {
+    async fn token(&self) -> SimpleLoginResult<Option<Arc<str>>> {
+        let metadata = tokio::fs::metadata(&self.path)
+            .await
+            .map_err(SimpleLoginError::TokenProvider)?;
+        let version = FileVersion {
+            modified: metadata
+                .modified()
+                .map_err(SimpleLoginError::TokenProvider)?,
+            len: metadata.len(),
+        };
+
+        if let Some((cached_version, token)) = self.cache().as_ref() {
+            if *cached_version == version {
+                return Ok(Some(token.clone()));
+            }
+        }
+
+        let token = tokio::fs::read_to_string(&self.path)
+            .await
+            .map_err(SimpleLoginError::TokenProvider)?;
+        let token = parse_token(&token)?;
+        *self.cache() = Some((version, token.clone()));
+
+        Ok(Some(token))
+    }
+}
+
+/// Token printed by a command (e.g. `pass show simplelogin`), keeping the first line.
+///
+/// The command is run on the first request and its output is kept until [`CommandToken::reload`]
+pub struct CommandToken {
+    program: String,
+    args: Vec<String>,
+    cache: tokio::sync::Mutex<Option<Arc<str>>>,
+}
+
+impl CommandToken {
+    pub fn new<S: Into<String>>(program: S) -> Self {
+        Self {
+            program: program.into(),
+            args: Vec::new(),
+            cache: Default::default(),
+        }
+    }
+
+    pub fn arg<S: Into<String>>(mut self, arg: S) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    /// Run the command again on the next request
+    pub async fn reload(&self) {
+        *self.cache.lock().await = None;
+    }
+}
+
+impl fmt::Debug for CommandToken {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CommandToken")
+            .field("program", &self.program)
+            .field("args", &self.args)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl TokenProvider for CommandToken {
+    async fn token(&self) -> SimpleLoginResult<Option<Arc<str>>> {
+        let mut cache = self.cache.lock().await;
+        if let Some(token) = cache.as_ref() {
+            return Ok(Some(token.clone()));
+        }
+
+        let output = tokio::process::Command::new(&self.program)
+            .args(&self.args)
+            .stdin(std::process::Stdio::inherit())
+            .stderr(std::process::Stdio::inherit())
+            .output()
+            .await
+            .map_err(SimpleLoginError::TokenProvider)?;
+
+        if !output.status.success() {
+            return Err(token_error(format!(
+                "'{}' exited with {}",
+                self.program, output.status
+            )));
+        }
+
+        let output = String::from_utf8(output.stdout)
+            .map_err(|_| token_error(format!("'{}' printed invalid utf-8", self.program)))?;
+        let token = parse_token(output.lines().next().unwrap_or_default())?;
+        *cache = Some(token.clone());
+
+        Ok(Some(token))
+    }
+}
+
+fn parse_token(token: &str) -> SimpleLoginResult<Arc<str>> {
+    match token.trim() {
+        "" => Err(token_error("empty token".to_owned())),
+        token => Ok(token.into()),
+    }
+}
+
+fn token_error(message: String) -> SimpleLoginError {
+    SimpleLoginError::TokenProvider(io::Error::new(io::ErrorKind::InvalidData, message))
+}
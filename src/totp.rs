@@ -0,0 +1,248 @@
+//! RFC 6238 TOTP codes, enabled with the `totp` feature.
+//!
+//! Used to drive the 2FA login without a human, see [`crate::AuthFlow::otp_provider`].
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use hmac::digest::core_api::BlockSizeUser;
+use hmac::digest::{Digest, KeyInit};
+use hmac::{Mac, SimpleHmac};
+use thiserror::Error;
+
+use crate::OtpProvider;
+
+#[derive(Error, Debug)]
+#[error("Invalid base32 TOTP secret")]
+pub struct InvalidTotpSecret;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TotpAlgorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+/// Generator of TOTP codes.
+///
+/// The defaults match the authenticator apps (and SimpleLogin): SHA1, 6 digits, 30s step.
+#[derive(Clone)]
+pub struct Totp {
+    secret: Vec<u8>,
+    pub algorithm: TotpAlgorithm,
+    /// Length of the codes, clamped to `6..=10`
+    pub digits: u32,
+    /// Duration of a code, in seconds
+    pub step: u64,
+    /// Offset added to the clock, in seconds, to compensate a drift with the server
+    pub skew: i64,
+}
+
+impl std::fmt::Debug for Totp {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Totp")
+            .field("secret", &"[REDACTED]")
+            .field("algorithm", &self.algorithm)
+            .field("digits", &self.digits)
+            .field("step", &self.step)
+            .field("skew", &self.skew)
+            .finish()
+    }
+}
+
+impl Totp {
+    const MIN_DIGITS: u32 = 6;
+    const MAX_DIGITS: u32 = 10;
+
+    /// Create a generator from a raw secret
+    pub fn new(secret: Vec<u8>) -> Self {
+        Self {
+            secret,
+            algorithm: TotpAlgorithm::Sha1,
+            digits: 6,
+            step: 30,
+            skew: 0,
+        }
+    }
+
+    /// Create a generator from a base32 secret, as shown when enabling the 2FA.
+    /// Spaces, padding and case are ignored
+    pub fn from_base32(secret: &str) -> Result<Self, InvalidTotpSecret> {
+        Ok(Self::new(decode_base32(secret)?))
+    }
+
+    pub fn with_algorithm(mut self, algorithm: TotpAlgorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Length of the codes, clamped to `6..=10` (RFC 4226 needs at least 6 digits and the
+    /// truncated code has at most 10)
+    pub fn with_digits(mut self, digits: u32) -> Self {
+        self.digits = digits.clamp(Self::MIN_DIGITS, Self::MAX_DIGITS);
+        self
+    }
+
+    pub fn with_step(mut self, step: u64) -> Self {
+        self.step = step;
+        self
+    }
+
+    pub fn with_skew(mut self, skew: i64) -> Self {
+        self.skew = skew;
+        self
+    }
+
+    /// Code for the current time
+    pub fn generate(&self) -> String {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |now| now.as_secs());
+
+        self.generate_at(now)
+    }
+
+    /// Code for a unix timestamp, in seconds
+    pub fn generate_at(&self, timestamp: u64) -> String {
+        let timestamp = timestamp.saturating_add_signed(self.skew);
+        let counter = (timestamp / self.step.max(1)).to_be_bytes();
+
+        let hash = match self.algorithm {
+            TotpAlgorithm::Sha1 => hmac::<sha1::Sha1>(&self.secret, &counter),
+            TotpAlgorithm::Sha256 => hmac::<sha2::Sha256>(&self.secret, &counter),
+            TotpAlgorithm::Sha512 => hmac::<sha2::Sha512>(&self.secret, &counter),
+        };
+
+        // Dynamic truncation (RFC 4226 section 5.3)
+        let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+        let code = u32::from_be_bytes([
+            hash[offset] & 0x7f,
+            hash[offset + 1],
+            hash[offset + 2],
+            hash[offset + 3],
+        ]);
+
+        let digits = self.digits.clamp(Self::MIN_DIGITS, Self::MAX_DIGITS);
+        let code = u64::from(code) % 10u64.pow(digits);
+        format!("{:0width$}", code, width = digits as usize)
+    }
+}
+
+#[async_trait]
+impl OtpProvider for Totp {
+    async fn otp(&self, _email: &str) -> Option<String> {
+        Some(self.generate())
+    }
+}
+
+fn hmac<D: Digest + BlockSizeUser>(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac =
+        <SimpleHmac<D> as KeyInit>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Decode RFC 4648 base32, without padding
+fn decode_base32(input: &str) -> Result<Vec<u8>, InvalidTotpSecret> {
+    let mut output = Vec::with_capacity(input.len() * 5 / 8);
+    let mut buffer = 0u64;
+    let mut bits = 0;
+
+    for c in input.chars().filter(|c| !c.is_whitespace() && *c != '=') {
+        let value = match c.to_ascii_uppercase() {
+            c @ 'A'..='Z' => c as u64 - 'A' as u64,
+            c @ '2'..='7' => c as u64 - '2' as u64 + 26,
+            _ => return Err(InvalidTotpSecret),
+        };
+
+        buffer = (buffer << 5) | value;
+        bits += 5;
+
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    if output.is_empty() {
+        return Err(InvalidTotpSecret);
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 6238 appendix B
+    const SEED_SHA1: &[u8] = b"12345678901234567890";
+    const SEED_SHA256: &[u8] = b"12345678901234567890123456789012";
+    const SEED_SHA512: &[u8] = b"1234567890123456789012345678901234567890123456789012345678901234";
+
+    const VECTORS: &[(u64, &str, &str, &str)] = &[
+        (59, "94287082", "46119246", "90693936"),
+        (1111111109, "07081804", "68084774", "25091201"),
+        (1111111111, "14050471", "67062674", "99943326"),
+        (1234567890, "89005924", "91819424", "93441116"),
+        (2000000000, "69279037", "90698825", "38618901"),
+        (20000000000, "65353130", "77737706", "47863826"),
+    ];
+
+    #[test]
+    fn rfc6238_vectors() {
+        let sha1 = Totp::new(SEED_SHA1.to_vec()).with_digits(8);
+        let sha256 = Totp::new(SEED_SHA256.to_vec())
+            .with_digits(8)
+            .with_algorithm(TotpAlgorithm::Sha256);
+        let sha512 = Totp::new(SEED_SHA512.to_vec())
+            .with_digits(8)
+            .with_algorithm(TotpAlgorithm::Sha512);
+
+        for (time, expected_sha1, expected_sha256, expected_sha512) in VECTORS {
+            assert_eq!(sha1.generate_at(*time), *expected_sha1, "SHA1 at {time}");
+            assert_eq!(
+                sha256.generate_at(*time),
+                *expected_sha256,
+                "SHA256 at {time}"
+            );
+            assert_eq!(
+                sha512.generate_at(*time),
+                *expected_sha512,
+                "SHA512 at {time}"
+            );
+        }
+    }
+
+    #[test]
+    fn base32_secret() {
+        // base32 of the SHA1 seed of the RFC
+        let totp = Totp::from_base32("gezd gnbv gy3t qojq gezd gnbv gy3t qojq").unwrap();
+        assert_eq!(totp.generate_at(59), "287082");
+
+        assert!(Totp::from_base32("not base32!").is_err());
+        assert!(Totp::from_base32("").is_err());
+    }
+
+    #[test]
+    fn step_and_skew() {
+        let totp = Totp::new(SEED_SHA1.to_vec()).with_digits(8);
+        let skewed = totp.clone().with_skew(-30);
+        assert_eq!(skewed.generate_at(89), totp.generate_at(59));
+
+        let long_step = totp.clone().with_step(60);
+        assert_eq!(long_step.generate_at(119), totp.generate_at(59));
+    }
+
+    #[test]
+    fn digits_clamped() {
+        let totp = Totp::new(SEED_SHA1.to_vec());
+        assert_eq!(totp.clone().with_digits(4).generate_at(59), "287082");
+        assert_eq!(totp.clone().with_digits(30).generate_at(59).len(), 10);
+
+        let mut unchecked = totp;
+        unchecked.digits = 20;
+        assert_eq!(unchecked.generate_at(59).len(), 10);
+    }
+}
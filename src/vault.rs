@@ -0,0 +1,402 @@
+//! Encrypted storage of the api keys, enabled with the `vault` feature.
+//!
+//! The keys are stored per instance (base url) and account in a file encrypted with XChaCha20-Poly1305,
+//! using a key derived from a passphrase with Argon2id.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+use crate::{Secret, SimpleLoginClient, SimpleLoginClientBuilder};
+
+const VERSION: u32 = 1;
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+/// Upper bounds of the Argon2 parameters read from a file, a tampered file could otherwise
+/// make `open` allocate gigabytes before the decryption fails
+const MAX_M_COST: u32 = 1024 * 1024;
+const MAX_T_COST: u32 = 16;
+const MAX_P_COST: u32 = 16;
+
+#[derive(Error, Debug)]
+pub enum VaultError {
+    #[error("Unable to read or write the vault")]
+    Io(#[from] io::Error),
+
+    #[error("Wrong passphrase or corrupted vault")]
+    Decrypt,
+
+    #[error("Invalid vault file: {0}")]
+    Invalid(String),
+
+    #[error("Unable to derive the key of the vault")]
+    Kdf,
+}
+
+/// Api keys stored in a passphrase encrypted file
+pub struct Vault {
+    path: PathBuf,
+    kdf: KdfParams,
+    salt: [u8; SALT_LEN],
+    key: Zeroizing<[u8; KEY_LEN]>,
+    /// api keys by base url then account
+    entries: BTreeMap<String, BTreeMap<String, Secret>>,
+}
+
+impl std::fmt::Debug for Vault {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Vault")
+            .field("path", &self.path)
+            .field("entries", &self.list().collect::<Vec<_>>())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Argon2id parameters, stored in the file so they can be raised later
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct KdfParams {
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self {
+            m_cost: Params::DEFAULT_M_COST,
+            t_cost: Params::DEFAULT_T_COST,
+            p_cost: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct VaultFile {
+    version: u32,
+    kdf: KdfParams,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+impl Vault {
+    /// Create an empty vault, written to `path` on [`Vault::save`]
+    pub fn create<P: Into<PathBuf>>(path: P, passphrase: &str) -> Result<Self, VaultError> {
+        let kdf = KdfParams::default();
+        let salt: [u8; SALT_LEN] = rand::random();
+        let key = derive_key(passphrase, &salt, kdf)?;
+
+        Ok(Self {
+            path: path.into(),
+            kdf,
+            salt,
+            key,
+            entries: BTreeMap::new(),
+        })
+    }
+
+    /// Load and decrypt the vault at `path`
+    pub fn open<P: Into<PathBuf>>(path: P, passphrase: &str) -> Result<Self, VaultError> {
+        let path = path.into();
+        let file: VaultFile = serde_json::from_slice(&fs::read(&path)?)
+            .map_err(|error| VaultError::Invalid(error.to_string()))?;
+
+        if file.version != VERSION {
+            return Err(VaultError::Invalid(format!(
+                "unsupported version {}",
+                file.version
+            )));
+        }
+
+        if file.kdf.m_cost > MAX_M_COST
+            || file.kdf.t_cost > MAX_T_COST
+            || file.kdf.p_cost > MAX_P_COST
+        {
+            return Err(VaultError::Invalid("kdf parameters too high".to_owned()));
+        }
+
+        let salt: [u8; SALT_LEN] = decode(&file.salt)?
+            .try_into()
+            .map_err(|_| VaultError::Invalid("invalid salt".to_owned()))?;
+        let nonce = decode(&file.nonce)?;
+        if nonce.len() != XNonce::default().len() {
+            return Err(VaultError::Invalid("invalid nonce".to_owned()));
+        }
+        let ciphertext = decode(&file.ciphertext)?;
+
+        let key = derive_key(passphrase, &salt, file.kdf)?;
+        let plaintext = XChaCha20Poly1305::new(key.as_ref().into())
+            .decrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: &aad(file.version),
+                },
+            )
+            .map(Zeroizing::new)
+            .map_err(|_| VaultError::Decrypt)?;
+
+        let entries = serde_json::from_slice(&plaintext)
+            .map_err(|error| VaultError::Invalid(error.to_string()))?;
+
+        Ok(Self {
+            path,
+            kdf: file.kdf,
+            salt,
+            key,
+            entries,
+        })
+    }
+
+    /// Open the vault at `path`, or create an empty one if the file does not exist
+    pub fn open_or_create<P: Into<PathBuf>>(path: P, passphrase: &str) -> Result<Self, VaultError> {
+        let path = path.into();
+        match path.exists() {
+            true => Self::open(path, passphrase),
+            false => Self::create(path, passphrase),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Encrypt and write the vault, replacing the file atomically
+    pub fn save(&self) -> Result<(), VaultError> {
+        let plaintext = Zeroizing::new(
+            serde_json::to_vec(&self.exposed_entries())
+                .map_err(|error| VaultError::Invalid(error.to_string()))?,
+        );
+
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = XChaCha20Poly1305::new(self.key.as_ref().into())
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: &plaintext,
+                    aad: &aad(VERSION),
+                },
+            )
+            .map_err(|_| VaultError::Decrypt)?;
+
+        let file = VaultFile {
+            version: VERSION,
+            kdf: self.kdf,
+            salt: STANDARD.encode(self.salt),
+            nonce: STANDARD.encode(nonce),
+            ciphertext: STANDARD.encode(ciphertext),
+        };
+        let file = serde_json::to_vec_pretty(&file)
+            .map_err(|error| VaultError::Invalid(error.to_string()))?;
+
+        // Appended rather than replacing the extension, which could be `.tmp` already
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        let tmp_path = PathBuf::from(tmp_path);
+        write_private(&tmp_path, &file)?;
+        fs::rename(&tmp_path, &self.path)?;
+
+        Ok(())
+    }
+
+    /// Api key of `account` on the instance at `base_url`
+    pub fn get(&self, base_url: &str, account: &str) -> Option<&Secret> {
+        self.entries
+            .get(base_url)
+            .and_then(|accounts| accounts.get(account))
+    }
+
+    /// Store the api key of `account` on the instance at `base_url`, returning the previous one
+    pub fn insert(&mut self, base_url: &str, account: &str, api_key: &Secret) -> Option<Secret> {
+        self.entries
+            .entry(base_url.to_owned())
+            .or_default()
+            .insert(account.to_owned(), api_key.clone())
+    }
+
+    /// Remove the api key of `account` on the instance at `base_url`, returning it
+    pub fn remove(&mut self, base_url: &str, account: &str) -> Option<Secret> {
+        let accounts = self.entries.get_mut(base_url)?;
+        let api_key = accounts.remove(account)?;
+        if accounts.is_empty() {
+            self.entries.remove(base_url);
+        }
+
+        Some(api_key)
+    }
+
+    /// Base urls and accounts stored in the vault, without their api keys
+    pub fn list(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries.iter().flat_map(|(base_url, accounts)| {
+            accounts
+                .keys()
+                .map(move |account| (base_url.as_str(), account.as_str()))
+        })
+    }
+
+    /// Encrypt the vault with a new passphrase, applied on the next [`Vault::save`]
+    pub fn rotate_passphrase(&mut self, passphrase: &str) -> Result<(), VaultError> {
+        let salt: [u8; SALT_LEN] = rand::random();
+        self.key = derive_key(passphrase, &salt, self.kdf)?;
+        self.salt = salt;
+
+        Ok(())
+    }
+
+    /// Builder of a client of the instance at `base_url` authenticated with the api key
+    /// of `account`, see [`SimpleLoginClient::builder`]
+    pub fn client_builder(
+        &self,
+        base_url: &str,
+        account: &str,
+    ) -> Option<SimpleLoginClientBuilder> {
+        let api_key = self.get(base_url, account)?;
+
+        Some(SimpleLoginClient::builder(base_url).token(api_key.clone()))
+    }
+
+    /// Entries with their api keys exposed, borrowed to be serialized
+    fn exposed_entries(&self) -> BTreeMap<&str, BTreeMap<&str, &str>> {
+        self.entries
+            .iter()
+            .map(|(base_url, accounts)| {
+                let accounts = accounts
+                    .iter()
+                    .map(|(account, api_key)| (account.as_str(), api_key.expose()))
+                    .collect();
+                (base_url.as_str(), accounts)
+            })
+            .collect()
+    }
+}
+
+fn derive_key(
+    passphrase: &str,
+    salt: &[u8],
+    kdf: KdfParams,
+) -> Result<Zeroizing<[u8; KEY_LEN]>, VaultError> {
+    let params = Params::new(kdf.m_cost, kdf.t_cost, kdf.p_cost, Some(KEY_LEN))
+        .map_err(|_| VaultError::Kdf)?;
+
+    let mut key = Zeroizing::new([0; KEY_LEN]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+        .map_err(|_| VaultError::Kdf)?;
+
+    Ok(key)
+}
+
+/// Authenticated data binding the ciphertext to the format
+fn aad(version: u32) -> Vec<u8> {
+    format!("simple_login_rs vault v{version}").into_bytes()
+}
+
+fn decode(value: &str) -> Result<Vec<u8>, VaultError> {
+    STANDARD
+        .decode(value)
+        .map_err(|error| VaultError::Invalid(error.to_string()))
+}
+
+/// Write a file readable only by its owner
+fn write_private(path: &Path, content: &[u8]) -> io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut file = options.open(path)?;
+    file.write_all(content)?;
+    file.sync_all()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vault_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("simple_login_vault_{}_{name}", std::process::id()))
+    }
+
+    fn api_key(vault: &Vault) -> Option<&str> {
+        vault
+            .get("https://app.simplelogin.io", "email")
+            .map(Secret::expose)
+    }
+
+    #[test]
+    fn save_and_open() {
+        let path = vault_path("save_and_open");
+        let mut vault = Vault::create(&path, "passphrase").unwrap();
+        vault.insert(
+            "https://app.simplelogin.io",
+            "email",
+            &Secret::new("api_key"),
+        );
+        vault.save().unwrap();
+
+        let vault = Vault::open(&path, "passphrase").unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(api_key(&vault), Some("api_key"));
+        assert_eq!(
+            vault.list().collect::<Vec<_>>(),
+            [("https://app.simplelogin.io", "email")]
+        );
+    }
+
+    #[test]
+    fn wrong_passphrase() {
+        let path = vault_path("wrong_passphrase");
+        Vault::create(&path, "passphrase").unwrap().save().unwrap();
+
+        let result = Vault::open(&path, "wrong passphrase");
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(result, Err(VaultError::Decrypt)));
+    }
+
+    #[test]
+    fn rotate_passphrase() {
+        let path = vault_path("rotate_passphrase");
+        let mut vault = Vault::create(&path, "passphrase").unwrap();
+        vault.insert(
+            "https://app.simplelogin.io",
+            "email",
+            &Secret::new("api_key"),
+        );
+        vault.rotate_passphrase("new passphrase").unwrap();
+        vault.save().unwrap();
+
+        let old = Vault::open(&path, "passphrase");
+        let new = Vault::open(&path, "new passphrase");
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(old, Err(VaultError::Decrypt)));
+        assert_eq!(api_key(&new.unwrap()), Some("api_key"));
+    }
+
+    #[test]
+    fn kdf_params_capped() {
+        let path = vault_path("kdf_params_capped");
+        Vault::create(&path, "passphrase").unwrap().save().unwrap();
+
+        let mut file: VaultFile = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+        file.kdf.m_cost = u32::MAX;
+        fs::write(&path, serde_json::to_vec(&file).unwrap()).unwrap();
+
+        let result = Vault::open(&path, "passphrase");
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(result, Err(VaultError::Invalid(_))));
+    }
+}